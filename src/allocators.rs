@@ -1,62 +1,241 @@
-use crate::{Item, MAX_INVENTORY_SIZE, Quality, Slot};
-use itertools::{Itertools, iproduct};
+use crate::{Item, Quality, Slot, WarehouseLayout};
+#[cfg(feature = "alloc-greedy")]
+use itertools::Itertools;
+use itertools::iproduct;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::{Debug, Display};
-use std::any::Any;
 
-// TODO: should be selectable AT COMPILE TIME
+/// Outcome of checking a single candidate slot for an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotCheck {
+    Available,
+    /// The item does not fit inside the warehouse from this slot.
+    OutOfBounds,
+    /// The slot is covered by an OverSized item stored in a previous zone.
+    BackwardBlocked,
+    /// The slot, or one of the following zones the item needs, is in use.
+    ForwardBlocked,
+    /// The slot is above the `max_row` of a Fragile item.
+    FragileRow,
+}
+
+/// How many candidate slots an allocation examined, and why they were rejected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocDiagnostics {
+    pub examined: usize,
+    pub out_of_bounds: usize,
+    pub backward_blocked: usize,
+    pub forward_blocked: usize,
+    pub fragile_row: usize,
+}
+
+impl AllocDiagnostics {
+    fn record(&mut self, check: SlotCheck) {
+        self.examined += 1;
+        match check {
+            SlotCheck::Available => {}
+            SlotCheck::OutOfBounds => self.out_of_bounds += 1,
+            SlotCheck::BackwardBlocked => self.backward_blocked += 1,
+            SlotCheck::ForwardBlocked => self.forward_blocked += 1,
+            SlotCheck::FragileRow => self.fragile_row += 1,
+        }
+    }
+}
+
+impl Display for AllocDiagnostics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} slots examined: {} out of bounds, {} covered by an OverSized item behind, {} blocked ahead, {} above the Fragile max. row",
+            self.examined, self.out_of_bounds, self.backward_blocked, self.forward_blocked, self.fragile_row
+        )
+    }
+}
+
+/// Result of a dry-run allocation, see [`AllocStrategy::explain`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllocExplanation {
+    pub slot: Option<Slot>, // slot that `alloc` would return
+    pub diagnostics: AllocDiagnostics,
+}
+
+/// A valid slot for an item, with the scores used to rank it (lower is better).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotSuggestion {
+    pub slot: Slot,
+    /// Manhattan distance from the base.
+    pub distance: usize,
+    /// Free zones left right before and after the item on its shelf (0 to 2), i.e. how many
+    /// gaps the placement creates or leaves open.
+    pub fragmentation: usize,
+}
+
+impl Display for SlotSuggestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (distance {}, {} free neighbour zones)",
+            self.slot, self.distance, self.fragmentation
+        )
+    }
+}
+
+/// Contract shared by every allocation strategy: for a given item and state of the warehouse,
+/// return a free slot that is valid for the item.
 pub trait AllocStrategy: Display + Debug {
     // FIXME: I don't like to require alloc to be &mut self,
     //        but using an internal state in RoundRobin requires it
     //        (otherwise we'd need to update internal state in a separate call,
     //        which might break the abstraction as GreedyAllocator doesn't need internal state)
-    fn alloc(&mut self, item: &Item, inventory: &HashMap<Slot, Item>) -> Option<Slot>;
+    /// Picks a slot for `item`, or `None` if there is no valid slot left.
+    fn alloc(
+        &mut self,
+        item: &Item,
+        occupancy: &HashMap<Slot, Slot>,
+        layout: &WarehouseLayout,
+    ) -> Option<Slot>;
+
+    /// Updates the strategy state with the outcome of an allocation (by default, nothing to do).
+    fn record_alloc(&mut self, _slot: Option<Slot>) {}
+
+    /// Slots in the order the strategy tries them (by default row by row, shelf by shelf).
+    fn candidates(&self, layout: &WarehouseLayout) -> Box<dyn Iterator<Item = Slot>> {
+        let layout = *layout;
+        Box::new(iproduct!(0..layout.rows, 0..layout.shelves, 0..layout.zones).map(Slot::from))
+    }
 
-    fn is_slot_available(&self, slot: &Slot, item: &Item, inventory: &HashMap<Slot, Item>) -> bool {
+    /// Dry run of [`alloc`](AllocStrategy::alloc): returns the slot it would pick with a
+    /// breakdown of the rejected candidates, without changing the strategy state.
+    fn explain(
+        &self,
+        item: &Item,
+        occupancy: &HashMap<Slot, Slot>,
+        layout: &WarehouseLayout,
+    ) -> AllocExplanation {
+        let mut diagnostics = AllocDiagnostics::default();
+        for slot in self.candidates(layout) {
+            let check = self.check_slot(&slot, item, occupancy, layout);
+            diagnostics.record(check);
+            if check == SlotCheck::Available {
+                return AllocExplanation {
+                    slot: Some(slot),
+                    diagnostics,
+                };
+            }
+        }
+        AllocExplanation {
+            slot: None,
+            diagnostics,
+        }
+    }
+
+    /// Up to `k` valid slots for `item`, fewest free neighbour zones first, then closest to the
+    /// base (ties in row, shelf, zone order).
+    ///
+    /// Every slot of the layout is ranked, whatever the order the strategy scans them in.
+    fn suggest(
+        &self,
+        item: &Item,
+        occupancy: &HashMap<Slot, Slot>,
+        layout: &WarehouseLayout,
+        k: usize,
+    ) -> Vec<SlotSuggestion> {
+        let mut suggestions = valid_slots(self, item, occupancy, layout);
+        suggestions.sort_by_key(|s| (s.fragmentation, s.distance)); // stable
+        suggestions.truncate(k);
+        suggestions
+    }
+
+    /// Checks whether `item` can be stored at `slot`, including the Fragile `max_row` limit.
+    fn check_slot(
+        &self,
+        slot: &Slot,
+        item: &Item,
+        occupancy: &HashMap<Slot, Slot>,
+        layout: &WarehouseLayout,
+    ) -> SlotCheck {
+        // an item covering no zone would never show up in the occupancy: it fits nowhere
         let size = self.get_item_size(item);
-        if slot.zone + size > MAX_INVENTORY_SIZE {
-            return false;
+        if size == 0 || !layout.contains(slot) || slot.zone.checked_add(size).is_none_or(|end| end > layout.zones) {
+            return SlotCheck::OutOfBounds;
+        }
+        if occupancy.get(slot).is_some_and(|head| head != slot) {
+            return SlotCheck::BackwardBlocked;
+        }
+        if !self.is_slot_available(slot, item, occupancy, layout) {
+            return SlotCheck::ForwardBlocked;
+        }
+        match &item.quality {
+            Quality::Fragile { max_row, .. } if slot.row > *max_row => SlotCheck::FragileRow,
+            _ => SlotCheck::Available,
         }
+    }
 
-        let end = slot.zone + size;
-        // let end = std::cmp::min(slot.zone + size, MAX_INVENTORY_SIZE);
-        // check if there are enough free zones from current position onwards
-        let is_blocked_forward = (slot.zone..end)
-            .any(|z| inventory.contains_key(&Slot::from((slot.row, slot.shelf, z))));
-        if is_blocked_forward {
+    /// Returns `true` if `item` fits at `slot`: inside `layout` and with enough free zones.
+    fn is_slot_available(
+        &self,
+        slot: &Slot,
+        item: &Item,
+        occupancy: &HashMap<Slot, Slot>,
+        layout: &WarehouseLayout,
+    ) -> bool {
+        if slot.row >= layout.rows || slot.shelf >= layout.shelves {
             return false;
         }
-        // check if there are previous items blocking current position
-        let is_blocked_backward = (0..slot.zone)
-            // skip empty positions and return refs to items of occupied positions
-            .filter_map(|zone| {
-                inventory
-                    .get(&Slot::from((slot.row, slot.shelf, zone)))
-                    .map(|item| (item, zone))
-            })
-            // extract Item size of occupied positions
-            .map(|(item, zone)| (self.get_item_size(item), zone))
-            // check if it blocks current position
-            .any(|(size, zone)| size + zone > slot.zone);
-        !is_blocked_backward // -> is_available
+        let size = self.get_item_size(item);
+        if slot.zone.checked_add(size).is_none_or(|end| end > layout.zones) {
+            return false;
+        }
+
+        // check if there are enough free zones from current position onwards
+        // (zones covered by OverSized items are indexed too, so no need to look backwards)
+        let is_blocked = item
+            .covered_slots(slot)
+            .any(|covered| occupancy.contains_key(&covered));
+        !is_blocked // -> is_available
     }
 
     fn get_item_size(&self, item: &Item) -> usize {
-        match item.quality {
-            Quality::Normal | Quality::Fragile { .. } => 1,
-            Quality::OverSized { size } => size,
-        }
+        item.size()
     }
 }
 
+// every valid slot for `item`, in row, shelf, zone order, with its (unsorted) scores
+fn valid_slots<S: AllocStrategy + ?Sized>(
+    strategy: &S,
+    item: &Item,
+    occupancy: &HashMap<Slot, Slot>,
+    layout: &WarehouseLayout,
+) -> Vec<SlotSuggestion> {
+    let size = strategy.get_item_size(item);
+    let is_free = |slot: Slot| !occupancy.contains_key(&slot);
+    iproduct!(0..layout.rows, 0..layout.shelves, 0..layout.zones)
+        .map(Slot::from)
+        .filter(|slot| strategy.check_slot(slot, item, occupancy, layout) == SlotCheck::Available)
+        .map(|slot| {
+            let (row, shelf, zone) = slot.as_tuple();
+            let before = zone > 0 && is_free(Slot::from((row, shelf, zone - 1)));
+            let after = zone + size < layout.zones && is_free(Slot::from((row, shelf, zone + size)));
+            SlotSuggestion {
+                slot,
+                distance: slot.distance(),
+                fragmentation: usize::from(before) + usize::from(after),
+            }
+        })
+        .collect()
+}
 
-#[derive(Debug)]
+/// Continues searching from the last allocated slot, ignoring zones freed in the meantime.
+#[cfg(feature = "alloc-round-robin")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct RoundRobinAllocator {
     prev_alloc: Option<Slot>,
 }
 
+#[cfg(feature = "alloc-round-robin")]
 impl RoundRobinAllocator {
-    fn get_prev_alloc(&self) -> &Option<Slot> {
+    pub fn get_prev_alloc(&self) -> &Option<Slot> {
         &self.prev_alloc
     }
 
@@ -64,14 +243,14 @@ impl RoundRobinAllocator {
         self.prev_alloc = new_alloc;
     }
 
-    fn get_start_pos(&self) -> (usize, usize, usize) {
+    fn get_start_pos(&self, layout: &WarehouseLayout) -> (usize, usize, usize) {
         match &self.prev_alloc {
             Some(slot) => {
                 let tuple = slot.as_tuple();
                 (
-                    if tuple.0 == MAX_INVENTORY_SIZE - 1 { 0 } else { tuple.0 },
-                    if tuple.1 == MAX_INVENTORY_SIZE - 1 { 0 } else { tuple.1 },
-                    if tuple.2 == MAX_INVENTORY_SIZE - 1 { 0 } else { tuple.2 },
+                    if tuple.0 + 1 >= layout.rows { 0 } else { tuple.0 },
+                    if tuple.1 + 1 >= layout.shelves { 0 } else { tuple.1 },
+                    if tuple.2 + 1 >= layout.zones { 0 } else { tuple.2 },
                 )
             },
             None => (0, 0, 0),
@@ -79,12 +258,7 @@ impl RoundRobinAllocator {
     }
 }
 
-impl Default for RoundRobinAllocator {
-    fn default() -> Self {
-        Self { prev_alloc: None }
-    }
-}
-
+#[cfg(feature = "alloc-round-robin")]
 impl Display for RoundRobinAllocator {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -95,40 +269,43 @@ impl Display for RoundRobinAllocator {
     }
 }
 
+#[cfg(feature = "alloc-round-robin")]
 impl AllocStrategy for RoundRobinAllocator {
-    fn alloc(&mut self, item: &Item, inventory: &HashMap<Slot, Item>) -> Option<Slot> {
-        // round-robin
-        let (row_start, shelf_start, zone_start) = self.get_start_pos();
-        for (row, shelf, zone) in iproduct!(
-            row_start..MAX_INVENTORY_SIZE,
-            shelf_start..MAX_INVENTORY_SIZE,
-            zone_start..MAX_INVENTORY_SIZE
-        ) {
-            let slot = Slot::from((row, shelf, zone));
-            // handles interactions with Quality::OverSized
-            if !self.is_slot_available(&slot, item, inventory) {
-                continue;
-            }
-            match &item.quality {
-                Quality::Normal | Quality::OverSized { .. } => {
-                    self.set_prev_alloc(Some(slot)); // Slot is Copy
-                    return Some(slot);
-                }
-                Quality::Fragile { max_row, .. } if &slot.row <= max_row => {
-                    self.set_prev_alloc(Some(slot)); // Slot is Copy
-                    return Some(slot);
-                }
-                _ => continue,
-            }
-        }
-        self.set_prev_alloc(None); // failed alloc, reset search indexing
-        None
+    fn alloc(
+        &mut self,
+        item: &Item,
+        occupancy: &HashMap<Slot, Slot>,
+        layout: &WarehouseLayout,
+    ) -> Option<Slot> {
+        let slot = self.explain(item, occupancy, layout).slot;
+        self.record_alloc(slot);
+        slot
+    }
+
+    fn record_alloc(&mut self, slot: Option<Slot>) {
+        self.set_prev_alloc(slot); // a failed alloc resets the search indexing
+    }
+
+    fn candidates(&self, layout: &WarehouseLayout) -> Box<dyn Iterator<Item = Slot>> {
+        // round-robin: resume from the last allocated slot
+        let (row_start, shelf_start, zone_start) = self.get_start_pos(layout);
+        Box::new(
+            iproduct!(
+                row_start..layout.rows,
+                shelf_start..layout.shelves,
+                zone_start..layout.zones
+            )
+            .map(Slot::from),
+        )
     }
 }
 
-#[derive(Debug)]
+/// Always picks the free slot closest to the base.
+#[cfg(feature = "alloc-greedy")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct GreedyAllocator {}
 
+#[cfg(feature = "alloc-greedy")]
 impl GreedyAllocator {
     // I think this implementation is a bit messy, but it was the best I could come up with.
     // My main worry was ensuring lazy-evaluation,
@@ -139,7 +316,8 @@ impl GreedyAllocator {
     // Then, we generate all possible permutations (itertools)
     // Then, we filter out repeated permutations (itertools)
     // Finally, we return each Slot
-    fn slots_by_distance(dist: usize) -> impl Iterator<Item = Slot> {
+    fn slots_by_distance(dist: usize, layout: &WarehouseLayout) -> impl Iterator<Item = Slot> + use<> {
+        let layout = *layout;
         (0..=dist)
             .flat_map(move |i| {
                 (0..=dist - i).flat_map(move |j| {
@@ -152,56 +330,291 @@ impl GreedyAllocator {
                 })
             })
             .unique()
-            .filter(|perm| {
-                perm[0] < MAX_INVENTORY_SIZE
-                    && perm[1] < MAX_INVENTORY_SIZE
-                    && perm[2] < MAX_INVENTORY_SIZE
+            .filter(move |perm| {
+                perm[0] < layout.rows && perm[1] < layout.shelves && perm[2] < layout.zones
             })
             .map(|perm| Slot::from((perm[0], perm[1], perm[2])))
     }
 }
 
-impl Default for GreedyAllocator {
-    fn default() -> Self {
-        Self {}
-    }
-}
-
+#[cfg(feature = "alloc-greedy")]
 impl Display for GreedyAllocator {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "GreedyAllocator")
     }
 }
 
+#[cfg(feature = "alloc-greedy")]
 impl AllocStrategy for GreedyAllocator {
-    fn alloc(&mut self, item: &Item, inventory: &HashMap<Slot, Item>) -> Option<Slot> {
-        for dist in 0..=3 * (MAX_INVENTORY_SIZE - 1) {
-            for slot in GreedyAllocator::slots_by_distance(dist) {
-                if !self.is_slot_available(&slot, item, inventory) {
-                    continue;
-                }
-                match &item.quality {
-                    Quality::Normal | Quality::OverSized { .. } => return Some(slot),
-                    Quality::Fragile { max_row, .. } if &slot.row <= max_row => {
-                        return Some(slot);
-                    }
-                    _ => continue,
+    fn alloc(
+        &mut self,
+        item: &Item,
+        occupancy: &HashMap<Slot, Slot>,
+        layout: &WarehouseLayout,
+    ) -> Option<Slot> {
+        self.explain(item, occupancy, layout).slot
+    }
+
+    fn candidates(&self, layout: &WarehouseLayout) -> Box<dyn Iterator<Item = Slot>> {
+        // closest to the base first
+        let layout = *layout;
+        Box::new(
+            (0..=layout.max_distance())
+                .flat_map(move |dist| GreedyAllocator::slots_by_distance(dist, &layout)),
+        )
+    }
+}
+
+/// Picks the tightest run of free zones the item fits in, closest to the base on ties,
+/// so that long runs are kept for OverSized items.
+#[cfg(feature = "alloc-best-fit")]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BestFitAllocator {}
+
+#[cfg(feature = "alloc-best-fit")]
+impl BestFitAllocator {
+    // length of the run of free zones that contains `slot`, on its shelf
+    fn run_length(slot: &Slot, occupancy: &HashMap<Slot, Slot>, layout: &WarehouseLayout) -> usize {
+        let (row, shelf, zone) = slot.as_tuple();
+        let is_free = |zone: &usize| !occupancy.contains_key(&Slot::from((row, shelf, *zone)));
+        let before = (0..zone).rev().take_while(is_free).count();
+        let after = (zone..layout.zones).take_while(is_free).count();
+        before + after
+    }
+}
+
+#[cfg(feature = "alloc-best-fit")]
+impl Display for BestFitAllocator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "BestFitAllocator")
+    }
+}
+
+#[cfg(feature = "alloc-best-fit")]
+impl AllocStrategy for BestFitAllocator {
+    fn alloc(
+        &mut self,
+        item: &Item,
+        occupancy: &HashMap<Slot, Slot>,
+        layout: &WarehouseLayout,
+    ) -> Option<Slot> {
+        self.explain(item, occupancy, layout).slot
+    }
+
+    fn explain(
+        &self,
+        item: &Item,
+        occupancy: &HashMap<Slot, Slot>,
+        layout: &WarehouseLayout,
+    ) -> AllocExplanation {
+        // unlike the other strategies, every slot has to be examined to find the tightest run
+        let mut diagnostics = AllocDiagnostics::default();
+        let mut best: Option<((usize, usize), Slot)> = None; // (run length, distance), slot
+        for slot in self.candidates(layout) {
+            let check = self.check_slot(&slot, item, occupancy, layout);
+            diagnostics.record(check);
+            if check == SlotCheck::Available {
+                let key = (Self::run_length(&slot, occupancy, layout), slot.distance());
+                if best.is_none_or(|(best_key, _)| key < best_key) {
+                    best = Some((key, slot));
                 }
             }
         }
-        None
+        AllocExplanation {
+            slot: best.map(|(_, slot)| slot),
+            diagnostics,
+        }
+    }
+
+    fn suggest(
+        &self,
+        item: &Item,
+        occupancy: &HashMap<Slot, Slot>,
+        layout: &WarehouseLayout,
+        k: usize,
+    ) -> Vec<SlotSuggestion> {
+        // same ranking as `alloc`: tightest run first, then closest to the base
+        let mut suggestions = valid_slots(self, item, occupancy, layout);
+        suggestions.sort_by_key(|s| (Self::run_length(&s.slot, occupancy, layout), s.distance)); // stable
+        suggestions.truncate(k);
+        suggestions
+    }
+}
+
+// Strategy used when none is picked explicitly: round-robin, then greedy, then best-fit,
+// depending on which ones were compiled in
+#[cfg(not(any(feature = "alloc-greedy", feature = "alloc-round-robin", feature = "alloc-best-fit")))]
+compile_error!(
+    "at least one allocation strategy feature must be enabled: `alloc-greedy`, `alloc-round-robin` or `alloc-best-fit`"
+);
+
+#[cfg(feature = "alloc-round-robin")]
+pub type DefaultAllocator = RoundRobinAllocator;
+#[cfg(all(feature = "alloc-greedy", not(feature = "alloc-round-robin")))]
+pub type DefaultAllocator = GreedyAllocator;
+#[cfg(all(
+    feature = "alloc-best-fit",
+    not(any(feature = "alloc-greedy", feature = "alloc-round-robin"))
+))]
+pub type DefaultAllocator = BestFitAllocator;
+
+/// Any of the strategies compiled into this crate, so it can be picked (and switched) at run time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Allocator {
+    #[cfg(feature = "alloc-greedy")]
+    Greedy(GreedyAllocator),
+    #[cfg(feature = "alloc-round-robin")]
+    RoundRobin(RoundRobinAllocator),
+    #[cfg(feature = "alloc-best-fit")]
+    BestFit(BestFitAllocator),
+}
+
+impl Default for Allocator {
+    fn default() -> Self {
+        Allocator::from(DefaultAllocator::default())
+    }
+}
+
+impl Display for Allocator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "alloc-greedy")]
+            Allocator::Greedy(a) => Display::fmt(a, f),
+            #[cfg(feature = "alloc-round-robin")]
+            Allocator::RoundRobin(a) => Display::fmt(a, f),
+            #[cfg(feature = "alloc-best-fit")]
+            Allocator::BestFit(a) => Display::fmt(a, f),
+        }
+    }
+}
+
+impl AllocStrategy for Allocator {
+    fn alloc(
+        &mut self,
+        item: &Item,
+        occupancy: &HashMap<Slot, Slot>,
+        layout: &WarehouseLayout,
+    ) -> Option<Slot> {
+        match self {
+            #[cfg(feature = "alloc-greedy")]
+            Allocator::Greedy(a) => a.alloc(item, occupancy, layout),
+            #[cfg(feature = "alloc-round-robin")]
+            Allocator::RoundRobin(a) => a.alloc(item, occupancy, layout),
+            #[cfg(feature = "alloc-best-fit")]
+            Allocator::BestFit(a) => a.alloc(item, occupancy, layout),
+        }
+    }
+
+    fn record_alloc(&mut self, slot: Option<Slot>) {
+        match self {
+            #[cfg(feature = "alloc-greedy")]
+            Allocator::Greedy(a) => a.record_alloc(slot),
+            #[cfg(feature = "alloc-round-robin")]
+            Allocator::RoundRobin(a) => a.record_alloc(slot),
+            #[cfg(feature = "alloc-best-fit")]
+            Allocator::BestFit(a) => a.record_alloc(slot),
+        }
+    }
+
+    fn candidates(&self, layout: &WarehouseLayout) -> Box<dyn Iterator<Item = Slot>> {
+        match self {
+            #[cfg(feature = "alloc-greedy")]
+            Allocator::Greedy(a) => a.candidates(layout),
+            #[cfg(feature = "alloc-round-robin")]
+            Allocator::RoundRobin(a) => a.candidates(layout),
+            #[cfg(feature = "alloc-best-fit")]
+            Allocator::BestFit(a) => a.candidates(layout),
+        }
+    }
+
+    fn explain(
+        &self,
+        item: &Item,
+        occupancy: &HashMap<Slot, Slot>,
+        layout: &WarehouseLayout,
+    ) -> AllocExplanation {
+        match self {
+            #[cfg(feature = "alloc-greedy")]
+            Allocator::Greedy(a) => a.explain(item, occupancy, layout),
+            #[cfg(feature = "alloc-round-robin")]
+            Allocator::RoundRobin(a) => a.explain(item, occupancy, layout),
+            #[cfg(feature = "alloc-best-fit")]
+            Allocator::BestFit(a) => a.explain(item, occupancy, layout),
+        }
+    }
+
+    fn suggest(
+        &self,
+        item: &Item,
+        occupancy: &HashMap<Slot, Slot>,
+        layout: &WarehouseLayout,
+        k: usize,
+    ) -> Vec<SlotSuggestion> {
+        match self {
+            #[cfg(feature = "alloc-greedy")]
+            Allocator::Greedy(a) => a.suggest(item, occupancy, layout, k),
+            #[cfg(feature = "alloc-round-robin")]
+            Allocator::RoundRobin(a) => a.suggest(item, occupancy, layout, k),
+            #[cfg(feature = "alloc-best-fit")]
+            Allocator::BestFit(a) => a.suggest(item, occupancy, layout, k),
+        }
+    }
+}
+
+#[cfg(feature = "alloc-greedy")]
+impl From<GreedyAllocator> for Allocator {
+    fn from(value: GreedyAllocator) -> Self {
+        Allocator::Greedy(value)
+    }
+}
+
+#[cfg(feature = "alloc-round-robin")]
+impl From<RoundRobinAllocator> for Allocator {
+    fn from(value: RoundRobinAllocator) -> Self {
+        Allocator::RoundRobin(value)
+    }
+}
+
+#[cfg(feature = "alloc-best-fit")]
+impl From<BestFitAllocator> for Allocator {
+    fn from(value: BestFitAllocator) -> Self {
+        Allocator::BestFit(value)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{GreedyAllocator, RoundRobinAllocator};
-    use crate::errors::ManagerError;
-    use crate::{Item, MAX_INVENTORY_SIZE, Manager, Quality, Slot};
-    use chrono::{Local, NaiveDateTime, TimeZone};
+    use super::{AllocDiagnostics, Allocator, DefaultAllocator, SlotSuggestion};
+    #[cfg(feature = "alloc-greedy")]
+    use super::GreedyAllocator;
+    #[cfg(feature = "alloc-round-robin")]
+    use super::RoundRobinAllocator;
+    #[cfg(feature = "alloc-best-fit")]
+    use super::BestFitAllocator;
+    use crate::{Item, Manager, ManagerError, Quality, Slot, WarehouseLayout};
+    use chrono::Local;
+    #[cfg(feature = "alloc-greedy")]
+    use chrono::{NaiveDateTime, TimeZone};
+
+    #[allow(clippy::vec_init_then_push)] // the pushes depend on the enabled features
+    fn compiled_allocators() -> Vec<Allocator> {
+        // every strategy enabled through cargo features
+        let mut allocators: Vec<Allocator> = Vec::new();
+        #[cfg(feature = "alloc-greedy")]
+        allocators.push(GreedyAllocator::default().into());
+        #[cfg(feature = "alloc-round-robin")]
+        allocators.push(RoundRobinAllocator::default().into());
+        #[cfg(feature = "alloc-best-fit")]
+        allocators.push(BestFitAllocator::default().into());
+        allocators
+    }
+
     #[test]
+    #[cfg(feature = "alloc-round-robin")]
     fn test_round_robin_allocator() {
+        let layout = WarehouseLayout::new(3, 3, 3);
         let mut manager = Manager::new(
+            layout,
             RoundRobinAllocator::default(),
             Vec::new(), // no filters
         );
@@ -211,7 +624,7 @@ mod tests {
             "A",
             1,
             Quality::OverSized {
-                size: MAX_INVENTORY_SIZE,
+                size: layout.zones,
             },
         ));
         assert!(result.is_ok());
@@ -223,22 +636,22 @@ mod tests {
         assert!(result.is_ok());
 
         assert_eq!(
-            manager.get_item(0, 0, 0),
+            manager.get_item(0, 0, 0).unwrap(),
             Some(&Item::new(
                 0,
                 "A",
                 1,
                 Quality::OverSized {
-                    size: MAX_INVENTORY_SIZE
+                    size: layout.zones
                 }
             ))
         );
         assert_eq!(
-            manager.get_item(0, 1, 0),
+            manager.get_item(0, 1, 0).unwrap(),
             Some(&Item::new(1, "B", 1, Quality::Normal))
         );
         assert_eq!(
-            manager.get_item(0, 1, 1),
+            manager.get_item(0, 1, 1).unwrap(),
             Some(&Item::new(2, "C", 1, Quality::Normal))
         );
 
@@ -246,13 +659,13 @@ mod tests {
         assert_eq!(manager.allocator.prev_alloc.unwrap(), Slot::from((0, 1, 1)));
 
         let result = manager.insert_item(Item::new(3, "D", 1, Quality::Normal));
-        manager.remove_item(0, 1, 0);
-        manager.remove_item(0, 1, 1);
+        manager.remove_item(0, 1, 0).unwrap();
+        manager.remove_item(0, 1, 1).unwrap();
 
         println!("{:#?}", &manager);
         assert!(result.is_ok());
         assert_eq!(
-            manager.get_item(0, 1, 2),
+            manager.get_item(0, 1, 2).unwrap(),
             Some(&Item::new(3, "D", 1, Quality::Normal))
         );
 
@@ -261,26 +674,43 @@ mod tests {
             "E",
             1,
             Quality::OverSized {
-                size: MAX_INVENTORY_SIZE + 1,
+                size: layout.zones + 1,
             },
         ));
 
-        assert!(result.is_err()); // failed alloc -> reset prev_alloc
-        assert!(manager.allocator.prev_alloc.is_none());
+        // longer than a shelf: rejected before allocating
+        assert!(result.is_err_and(|err| matches!(err, ManagerError::InvalidItemSize { .. })));
+        assert_eq!(manager.allocator.prev_alloc, Some(Slot::from((0, 1, 2))));
 
         let result = manager.insert_item(Item::new(5, "F", 1, Quality::OverSized { size: 2 })); // fills spot opened by the two removals
 
         assert!(result.is_ok());
         println!("{:#?}", &manager);
         assert_eq!(
-            manager.get_item(0, 1, 0),
+            manager.get_item(0, 1, 0).unwrap(),
             Some(&Item::new(5, "F", 1, Quality::OverSized { size: 2 }))
         );
+
+        // the error describes the scan that failed, from the last allocated slot
+        let mut manager = Manager::new(WarehouseLayout::new(1, 1, 2), RoundRobinAllocator::default(), Vec::new());
+        manager.insert_item(Item::new(0, "A", 1, Quality::Normal)).unwrap();
+        let result = manager.insert_item(Item::new(1, "B", 1, Quality::OverSized { size: 2 }));
+        assert!(result.is_err_and(|err| matches!(
+            err,
+            ManagerError::FailedAllocation { allocator, diagnostics, .. }
+                if allocator.contains("prev_alloc: Some([0|0|0])")
+                    && diagnostics.examined == 2
+                    && diagnostics.out_of_bounds == 1
+        )));
+        assert!(manager.allocator.prev_alloc.is_none()); // failed alloc -> reset prev_alloc
     }
 
     #[test]
+    #[cfg(feature = "alloc-greedy")]
     fn test_greedy_allocator() {
+        let layout = WarehouseLayout::new(3, 3, 3);
         let mut manager = Manager::new(
+            layout,
             GreedyAllocator {},
             Vec::new(), // no filters
         );
@@ -290,7 +720,7 @@ mod tests {
             "A",
             1,
             Quality::OverSized {
-                size: MAX_INVENTORY_SIZE,
+                size: layout.zones,
             },
         ));
         assert!(result.is_ok());
@@ -302,22 +732,22 @@ mod tests {
         assert!(result.is_ok());
 
         assert_eq!(
-            manager.get_item(0, 0, 0),
+            manager.get_item(0, 0, 0).unwrap(),
             Some(&Item::new(
                 0,
                 "A",
                 1,
                 Quality::OverSized {
-                    size: MAX_INVENTORY_SIZE
+                    size: layout.zones
                 }
             ))
         );
         assert_eq!(
-            manager.get_item(0, 1, 0),
+            manager.get_item(0, 1, 0).unwrap(),
             Some(&Item::new(1, "B", 1, Quality::Normal))
         );
         assert_eq!(
-            manager.get_item(1, 0, 0),
+            manager.get_item(1, 0, 0).unwrap(),
             Some(&Item::new(2, "C", 1, Quality::Normal))
         );
 
@@ -325,7 +755,7 @@ mod tests {
 
         assert!(result.is_ok());
         assert_eq!(
-            manager.get_item(0, 2, 0),
+            manager.get_item(0, 2, 0).unwrap(),
             Some(&Item::new(3, "D", 1, Quality::Normal))
         );
 
@@ -338,20 +768,20 @@ mod tests {
             "E",
             1,
             Quality::Fragile {
-                expiration_date: exp_date.clone(),
+                expiration_date: exp_date,
                 max_row: 1,
             },
         ));
 
         assert!(result.is_ok());
         assert_eq!(
-            manager.get_item(0, 1, 1),
+            manager.get_item(0, 1, 1).unwrap(),
             Some(&Item::new(
                 4,
                 "E",
                 1,
                 Quality::Fragile {
-                    expiration_date: exp_date.clone(),
+                    expiration_date: exp_date,
                     max_row: 1
                 }
             ))
@@ -361,7 +791,7 @@ mod tests {
 
         assert!(result.is_ok());
         assert_eq!(
-            manager.get_item(2, 0, 0),
+            manager.get_item(2, 0, 0).unwrap(),
             Some(&Item::new(5, "F", 1, Quality::Normal))
         );
 
@@ -369,8 +799,174 @@ mod tests {
 
         assert!(result.is_ok());
         assert_eq!(
-            manager.get_item(1, 0, 1),
+            manager.get_item(1, 0, 1).unwrap(),
             Some(&Item::new(6, "G", 1, Quality::Normal))
         );
     }
+
+    #[test]
+    #[cfg(all(feature = "alloc-greedy", feature = "alloc-round-robin"))]
+    fn test_switch_allocator() {
+        let layout = WarehouseLayout::new(3, 3, 3);
+        let mut manager = Manager::new(layout, Allocator::from(RoundRobinAllocator::default()), Vec::new());
+
+        manager.insert_item(Item::new(0, "A", 1, Quality::Normal)).unwrap();
+        manager.insert_item(Item::new(1, "B", 1, Quality::Normal)).unwrap();
+        manager.remove_item(0, 0, 0).unwrap();
+        // round-robin ignores the zone that was just freed
+        assert_eq!(manager.insert_item(Item::new(2, "C", 1, Quality::Normal)).unwrap().slot, Slot::from((0, 0, 2)));
+
+        let previous = manager.set_allocator(Allocator::from(GreedyAllocator {}));
+        assert!(matches!(previous, Allocator::RoundRobin(_)));
+        assert_eq!(manager.count_id(1) + manager.count_id(2), 2); // inventory is kept
+        // greedy fills it
+        assert_eq!(manager.insert_item(Item::new(3, "D", 1, Quality::Normal)).unwrap().slot, Slot::from((0, 0, 0)));
+    }
+
+    #[test]
+    fn test_allocators_respect_layout() {
+        let layout = WarehouseLayout::new(2, 4, 5);
+        for allocator in compiled_allocators() {
+            let mut manager = Manager::new(layout, allocator.clone(), Vec::new());
+            for _ in 0..2 * 4 * 5 {
+                assert!(manager.insert_item(Item::new(0, "A", 1, Quality::Normal)).is_ok());
+            }
+            assert!(manager.get_item(1, 3, 4).unwrap().is_some());
+
+            // warehouse is full
+            assert!(manager.insert_item(Item::new(0, "A", 1, Quality::Normal)).is_err());
+
+            // an OverSized item can never be wider than a shelf
+            let mut manager = Manager::new(layout, allocator, Vec::new());
+            let result = manager.insert_item(Item::new(1, "B", 1, Quality::OverSized { size: 6 }));
+            assert!(result.is_err());
+            let result = manager.insert_item(Item::new(1, "B", 1, Quality::OverSized { size: 5 }));
+            assert!(result.is_ok());
+        }
+    }
+
+    #[test]
+    fn test_explain_allocation() {
+        #[cfg(feature = "alloc-round-robin")]
+        {
+            let layout = WarehouseLayout::new(3, 3, 3);
+            let mut manager = Manager::new(layout, RoundRobinAllocator::default(), Vec::new());
+            manager.insert_item(Item::new(0, "A", 1, Quality::OverSized { size: 3 })).unwrap();
+
+            // dry run does not move the round-robin position
+            let explanation = manager.explain_allocation(&Item::new(1, "B", 1, Quality::Normal));
+            assert_eq!(explanation.slot, Some(Slot::from((0, 1, 0))));
+            assert_eq!(
+                explanation.diagnostics,
+                AllocDiagnostics { examined: 4, forward_blocked: 1, backward_blocked: 2, ..Default::default() }
+            );
+            assert_eq!(manager.allocator.prev_alloc, Some(Slot::from((0, 0, 0))));
+            assert_eq!(manager.insert_item(Item::new(1, "B", 1, Quality::Normal)).unwrap().slot, explanation.slot.unwrap());
+        }
+
+        // a Fragile item that can only go to the (full) first row
+        let layout = WarehouseLayout::new(2, 1, 2);
+        let mut manager = Manager::new(layout, DefaultAllocator::default(), Vec::new());
+        manager.insert_item(Item::new(0, "A", 1, Quality::OverSized { size: 2 })).unwrap();
+        let expiration_date = Local::now();
+        let fragile = Item::new(1, "B", 1, Quality::Fragile { expiration_date, max_row: 0 });
+        let explanation = manager.explain_allocation(&fragile);
+        assert_eq!(explanation.slot, None);
+        let expected = AllocDiagnostics {
+            examined: 4,
+            out_of_bounds: 0,
+            backward_blocked: 1,
+            forward_blocked: 1,
+            fragile_row: 2,
+        };
+        assert_eq!(explanation.diagnostics, expected);
+        assert!(manager.insert_item(fragile).is_err_and(|err| matches!(
+            err,
+            ManagerError::FailedAllocation { diagnostics, .. } if *diagnostics == expected
+        )));
+
+        let explanation = manager.explain_allocation(&Item::new(2, "C", 1, Quality::OverSized { size: 3 }));
+        assert_eq!(explanation.diagnostics.out_of_bounds, 4);
+    }
+
+    #[test]
+    fn test_suggest_slots() {
+        let layout = WarehouseLayout::new(3, 3, 3);
+        let mut manager = Manager::new(layout, DefaultAllocator::default(), Vec::new());
+        manager.place_item_at(Slot::from((0, 0, 0)), Item::new(0, "A", 1, Quality::Normal)).unwrap();
+        manager.place_item_at(Slot::from((0, 0, 2)), Item::new(1, "B", 1, Quality::Normal)).unwrap();
+        assert!(manager.get_item(0, 0, 2).unwrap().unwrap().timestamp().is_some());
+
+        // the gap between the two items is the tightest fit
+        let normal = Item::new(2, "C", 1, Quality::Normal);
+        let suggestions = manager.suggest_slots(&normal, 2);
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(
+            suggestions[0],
+            SlotSuggestion { slot: Slot::from((0, 0, 1)), distance: 1, fragmentation: 0 }
+        );
+        assert_eq!(suggestions[1].fragmentation, 1);
+        assert_eq!(manager.suggest_slots(&normal, 100).len(), 27 - 2);
+
+        // an OverSized item filling a whole shelf leaves no gap
+        let oversized = Item::new(3, "D", 1, Quality::OverSized { size: 3 });
+        let suggestions = manager.suggest_slots(&oversized, 3);
+        assert!(suggestions.iter().all(|s| s.fragmentation == 0 && s.slot.zone() == 0));
+        assert_eq!(suggestions[0].slot, Slot::from((0, 1, 0)));
+
+        // the chosen slot is still checked before storing the item
+        assert!(manager.place_item_at(Slot::from((0, 0, 2)), normal.clone()).is_err_and(|err| matches!(
+            err,
+            ManagerError::SlotOccupied { head, .. } if head == Slot::from((0, 0, 2))
+        )));
+        assert_eq!(manager.place_item_at(suggestions[0].slot, oversized).unwrap().slot, Slot::from((0, 1, 0)));
+        assert_eq!(manager.count_id(3), 1);
+
+        // every free slot is ranked, not only the ones the strategy scans next
+        for allocator in compiled_allocators() {
+            let mut manager = Manager::new(layout, allocator, Vec::new());
+            for _ in 0..5 {
+                manager.insert_item(Item::new(0, "A", 1, Quality::Normal)).unwrap();
+            }
+            let suggestions = manager.suggest_slots(&normal, 100);
+            assert_eq!(suggestions.len(), 27 - 5);
+            assert!(suggestions.iter().any(|s| s.slot.shelf() == 0 && s.slot.zone() == 0));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc-best-fit")]
+    fn test_best_fit_allocator() {
+        let layout = WarehouseLayout::new(3, 3, 5);
+        let mut manager = Manager::new(layout, Allocator::from(BestFitAllocator::default()), Vec::new());
+        // free runs on the first row: shelf 0 -> 2 + 2 zones, shelf 1 -> 3 + 1 zones, shelf 2 -> 5 zones
+        manager.place_item_at(Slot::from((0, 0, 2)), Item::new(0, "A", 1, Quality::Normal)).unwrap();
+        manager.place_item_at(Slot::from((0, 1, 3)), Item::new(0, "A", 1, Quality::Normal)).unwrap();
+
+        // a single zone goes into the 1-zone gap, even if it is further away
+        let explanation = manager.explain_allocation(&Item::new(1, "B", 1, Quality::Normal));
+        assert_eq!(explanation.slot, Some(Slot::from((0, 1, 4))));
+        assert_eq!(explanation.diagnostics.examined, layout.capacity()); // every slot is examined
+        // suggestions are ranked the same way
+        let suggestions = manager.suggest_slots(&Item::new(1, "B", 1, Quality::Normal), 2);
+        assert_eq!(suggestions[0].slot, Slot::from((0, 1, 4)));
+        assert_eq!(suggestions[1].slot, Slot::from((0, 0, 0)));
+        assert_eq!(manager.insert_item(Item::new(1, "B", 1, Quality::Normal)).unwrap().slot, Slot::from((0, 1, 4)));
+
+        // same run length: closest to the base first
+        let oversized = Item::new(2, "C", 1, Quality::OverSized { size: 2 });
+        assert_eq!(manager.insert_item(oversized.clone()).unwrap().slot, Slot::from((0, 0, 0)));
+        assert_eq!(manager.insert_item(oversized).unwrap().slot, Slot::from((0, 0, 3)));
+
+        // a 3-zone pallet takes the 3-zone run instead of splitting a 5-zone one
+        let oversized = Item::new(3, "D", 1, Quality::OverSized { size: 3 });
+        assert_eq!(manager.insert_item(oversized.clone()).unwrap().slot, Slot::from((0, 1, 0)));
+        assert_eq!(manager.insert_item(oversized).unwrap().slot, Slot::from((1, 0, 0))); // then by distance
+
+        // Fragile items still respect their max. row
+        let expiration_date = Local::now();
+        let fragile = Item::new(4, "E", 1, Quality::Fragile { expiration_date, max_row: 0 });
+        assert_eq!(manager.insert_item(fragile.clone()).unwrap().slot, Slot::from((0, 2, 0)));
+        assert_eq!(manager.insert_item(fragile).unwrap().slot, Slot::from((0, 2, 1)));
+    }
 }
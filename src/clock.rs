@@ -0,0 +1,38 @@
+use chrono::{DateTime, Local};
+use std::fmt::Debug;
+
+/// Source of the current time used by the [`Manager`](crate::Manager) and its filters.
+///
+/// Replace it (see [`Manager::set_clock`](crate::Manager::set_clock)) to test or simulate
+/// time-dependent rules.
+pub trait Clock: Debug {
+    fn now(&self) -> DateTime<Local>;
+}
+
+/// Wall-clock time, the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// Always returns the same instant.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock {
+    now: DateTime<Local>,
+}
+
+impl FixedClock {
+    pub fn new(now: DateTime<Local>) -> Self {
+        FixedClock { now }
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Local> {
+        self.now
+    }
+}
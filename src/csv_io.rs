@@ -0,0 +1,282 @@
+use crate::{AllocStrategy, FilterVerdict, Insertion, Item, Manager, ManagerError, Quality, Slot};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S %:z";
+const LOCAL_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S"; // same format as the TUI prompts
+
+/// One row of a CSV file: a single pallet.
+///
+/// The slot columns may be left empty when importing with [`ImportMode::Allocate`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CsvRecord {
+    pub row: Option<usize>,
+    pub shelf: Option<usize>,
+    pub zone: Option<usize>,
+    pub id: usize,
+    pub name: String,
+    pub quantity: usize,
+    pub quality: String,         // Normal, OverSized or Fragile
+    pub size: Option<usize>,     // only for OverSized items
+    pub max_row: Option<usize>,  // only for Fragile items
+    pub timestamp: Option<String>,
+    pub expiration_date: Option<String>, // only for Fragile items
+}
+
+impl CsvRecord {
+    fn from_item(slot: &Slot, item: &Item) -> Self {
+        let (size, max_row, expiration_date) = match item.quality {
+            Quality::Fragile {
+                expiration_date,
+                max_row,
+            } => (None, Some(max_row), Some(expiration_date)),
+            Quality::OverSized { size } => (Some(size), None, None),
+            Quality::Normal => (None, None, None),
+        };
+        Self {
+            row: Some(slot.row),
+            shelf: Some(slot.shelf),
+            zone: Some(slot.zone),
+            id: item.id,
+            name: item.name.clone(),
+            quantity: item.quantity,
+            quality: item.quality.to_string(),
+            size,
+            max_row,
+            timestamp: item.timestamp.map(|t| t.format(DATE_FORMAT).to_string()),
+            expiration_date: expiration_date.map(|t| t.format(DATE_FORMAT).to_string()),
+        }
+    }
+
+    fn slot(&self) -> Result<Slot, ManagerError> {
+        match (self.row, self.shelf, self.zone) {
+            (Some(row), Some(shelf), Some(zone)) => Ok(Slot::from((row, shelf, zone))),
+            _ => Err(malformed("missing row, shelf or zone")),
+        }
+    }
+
+    fn to_item(&self) -> Result<Item, ManagerError> {
+        let quality = match self.quality.as_str() {
+            "Normal" => Quality::Normal,
+            "OverSized" => Quality::OverSized {
+                size: self.size.ok_or_else(|| malformed("missing size of OverSized item"))?,
+            },
+            "Fragile" => Quality::Fragile {
+                expiration_date: parse_date(
+                    self.expiration_date
+                        .as_deref()
+                        .ok_or_else(|| malformed("missing expiration_date of Fragile item"))?,
+                )?,
+                max_row: self
+                    .max_row
+                    .ok_or_else(|| malformed("missing max_row of Fragile item"))?,
+            },
+            other => return Err(malformed(&format!("unknown quality {other:?}"))),
+        };
+        let mut item = Item::new(self.id, &self.name, self.quantity, quality);
+        item.timestamp = self.timestamp.as_deref().map(parse_date).transpose()?;
+        Ok(item)
+    }
+}
+
+fn malformed(reason: &str) -> ManagerError {
+    ManagerError::MalformedRecord {
+        reason: reason.to_string(),
+    }
+}
+
+fn parse_date(value: &str) -> Result<DateTime<Local>, ManagerError> {
+    // accept dates with an explicit offset (as exported) or in local time (as typed in the TUI)
+    if let Ok(date) = DateTime::parse_from_str(value, DATE_FORMAT) {
+        return Ok(date.with_timezone(&Local));
+    }
+    NaiveDateTime::parse_from_str(value, LOCAL_DATE_FORMAT)
+        .ok()
+        .and_then(|date| Local.from_local_datetime(&date).earliest())
+        .ok_or_else(|| malformed(&format!("invalid date {value:?}")))
+}
+
+/// How imported rows are placed in the warehouse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Store every item at the slot given in the file, keeping its timestamp.
+    AtSlots,
+    /// Ignore the slot columns and go through the allocator, like [`Manager::insert_item`].
+    Allocate,
+}
+
+/// Outcome of a CSV import: rows are processed independently.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub imported: Vec<(u64, Slot)>,          // line, slot where the item was stored
+    pub rejected: Vec<(u64, ManagerError)>, // line, reason
+    pub warnings: Vec<(u64, FilterVerdict)>, // line, soft filter triggered by an imported row
+}
+
+impl<A> Manager<A>
+where
+    A: AllocStrategy,
+{
+    /// Writes the inventory as CSV, one row per pallet, ordered by slot.
+    pub fn export_csv(&self, writer: impl Write) -> Result<(), ManagerError> {
+        let mut entries: Vec<(&Slot, &Item)> = self.inventory.iter().collect();
+        entries.sort_by_key(|(slot, _)| slot.as_tuple());
+
+        let mut writer = csv::Writer::from_writer(writer);
+        for (slot, item) in entries {
+            writer.serialize(CsvRecord::from_item(slot, item))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn export_csv_file(&self, path: impl AsRef<Path>) -> Result<(), ManagerError> {
+        self.export_csv(File::create(path)?)
+    }
+
+    /// Reads pallets from CSV. Filters are checked for every row.
+    pub fn import_csv(&mut self, reader: impl Read, mode: ImportMode) -> ImportReport {
+        let mut report = ImportReport::default();
+        let mut reader = csv::Reader::from_reader(reader);
+        let headers = match reader.headers() {
+            Ok(headers) => headers.clone(),
+            Err(err) => {
+                report.rejected.push((1, err.into()));
+                return report;
+            }
+        };
+        let mut raw = csv::StringRecord::new();
+        loop {
+            match reader.read_record(&mut raw) {
+                Ok(false) => break,
+                Ok(true) => {
+                    let line = raw.position().map_or(0, |p| p.line());
+                    let result = raw
+                        .deserialize::<CsvRecord>(Some(&headers))
+                        .map_err(ManagerError::from)
+                        .and_then(|record| self._import_record(&record, mode));
+                    match result {
+                        Ok(Insertion { slot, warnings }) => {
+                            report.imported.push((line, slot));
+                            report.warnings.extend(warnings.into_iter().map(|w| (line, w)));
+                        }
+                        Err(err) => report.rejected.push((line, err)),
+                    }
+                }
+                Err(err) => {
+                    let line = err.position().map_or(0, |p| p.line());
+                    // a row with a wrong number of fields can be skipped, anything else is fatal
+                    let is_fatal = !matches!(err.kind(), csv::ErrorKind::UnequalLengths { .. });
+                    report.rejected.push((line, err.into()));
+                    if is_fatal {
+                        break;
+                    }
+                }
+            }
+        }
+        report
+    }
+
+    pub fn import_csv_file(
+        &mut self,
+        path: impl AsRef<Path>,
+        mode: ImportMode,
+    ) -> Result<ImportReport, ManagerError> {
+        Ok(self.import_csv(File::open(path)?, mode))
+    }
+
+    fn _import_record(&mut self, record: &CsvRecord, mode: ImportMode) -> Result<Insertion, ManagerError> {
+        let mut item = record.to_item()?;
+        match mode {
+            ImportMode::Allocate => self.insert_item(item),
+            ImportMode::AtSlots => {
+                let slot = record.slot()?;
+                if item.timestamp.is_none() {
+                    item.update_timestamp(self.clock.now());
+                }
+                self._place_item(slot, item)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ImportMode;
+    use crate::allocators::DefaultAllocator;
+    use crate::errors::ManagerError;
+    use crate::filters::{Filter, LimitItemQuantity};
+    #[cfg(all(feature = "alloc-greedy", not(feature = "alloc-round-robin")))]
+    use crate::Slot;
+    use crate::{Item, Manager, Quality, WarehouseLayout};
+    use chrono::{Local, NaiveDateTime, TimeZone};
+
+    #[test]
+    fn test_csv_roundtrip() {
+        let exp_date =
+            NaiveDateTime::parse_from_str("2020-01-01 14:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let exp_date = Local.from_local_datetime(&exp_date).unwrap(); // DateTime<Local>
+
+        let layout = WarehouseLayout::new(3, 3, 3);
+        let mut manager = Manager::new(layout, DefaultAllocator::default(), Vec::new());
+        manager.insert_item(Item::new(0, "Flour, fine", 10, Quality::Normal)).unwrap();
+        manager.insert_item(Item::new(1, "Wood", 5, Quality::OverSized { size: 2 })).unwrap();
+        manager.insert_item(Item::new(2, "Glass", 2, Quality::Fragile { expiration_date: exp_date, max_row: 1 })).unwrap();
+
+        let mut csv = Vec::new();
+        manager.export_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv.starts_with("row,shelf,zone,id,name,quantity,quality,size,max_row,timestamp,expiration_date\n"));
+        assert!(csv.contains("0,0,0,0,\"Flour, fine\",10,Normal,,,"));
+
+        let mut imported = Manager::new(layout, DefaultAllocator::default(), Vec::new());
+        let report = imported.import_csv(csv.as_bytes(), ImportMode::AtSlots);
+        assert!(report.rejected.is_empty());
+        assert_eq!(report.imported.len(), 3);
+        assert_eq!(imported.inventory, manager.inventory);
+        assert_eq!(imported.map_occupancy, manager.map_occupancy);
+        assert_eq!(imported.map_dates, manager.map_dates);
+        assert_eq!(
+            imported.get_item(0, 0, 0).unwrap().unwrap().timestamp().map(|t| t.timestamp()),
+            manager.get_item(0, 0, 0).unwrap().unwrap().timestamp().map(|t| t.timestamp())
+        );
+    }
+
+    #[test]
+    fn test_csv_import_report() {
+        let filters: Vec<Box<dyn Filter>> = vec![Box::from(LimitItemQuantity::new(0, 15))];
+        let mut manager = Manager::new(WarehouseLayout::new(3, 3, 3), DefaultAllocator::default(), filters);
+        let csv = "\
+row,shelf,zone,id,name,quantity,quality,size,max_row,timestamp,expiration_date
+,,,0,Flour,10,Normal,,,,
+,,,0,Flour,10,Normal,,,,
+,,,1,Wood,1,OverSized,,,,
+,,,2,Glass,1,Fragile,,1,,2020-01-01 14:30:00
+,,,3,Steel,1,Heavy,,,,
+9,9,9,4,Salt,1,Normal,,,,
+";
+        let report = manager.import_csv(csv.as_bytes(), ImportMode::Allocate);
+        // slot columns are ignored when allocating
+        let imported: Vec<u64> = report.imported.iter().map(|(line, _)| *line).collect();
+        assert_eq!(imported, vec![2, 5, 7]);
+        #[cfg(all(feature = "alloc-greedy", not(feature = "alloc-round-robin")))]
+        assert_eq!(
+            report.imported,
+            vec![(2, Slot::from((0, 0, 0))), (5, Slot::from((0, 0, 1))), (7, Slot::from((0, 1, 0)))]
+        );
+        let rejected: Vec<u64> = report.rejected.iter().map(|(line, _)| *line).collect();
+        assert_eq!(rejected, vec![3, 4, 6]);
+        assert!(matches!(report.rejected[0].1, ManagerError::FilteredItem { .. }));
+        assert!(matches!(report.rejected[1].1, ManagerError::MalformedRecord { .. }));
+
+        // explicit slots are validated
+        let report = manager.import_csv(csv.as_bytes(), ImportMode::AtSlots);
+        assert!(report.imported.is_empty());
+        assert!(matches!(report.rejected.last().unwrap(), (7, ManagerError::InvalidSlot { .. })));
+        assert!(matches!(report.rejected[0], (2, ManagerError::MalformedRecord { .. })));
+    }
+}
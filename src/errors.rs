@@ -1,18 +1,65 @@
 use thiserror::Error;
 
-use crate::{Item, Slot};
+use crate::allocators::AllocDiagnostics;
+use crate::filters::FilterVerdict;
+use crate::{Item, Slot, WarehouseLayout};
+use itertools::Itertools;
+
+/// Errors returned by the [`Manager`](crate::Manager).
 #[derive(Error, Debug)]
 pub enum ManagerError {
-    #[error("{item:?} was rejected by some filter: {filters:?}")]
+    #[error("{item:?} was rejected by {}", .filters.iter().join("; "))]
     FilteredItem {
         item: Item,
-        filters: Vec<String>, // FIXME: ideally, should be the triggered filter
+        filters: Vec<FilterVerdict>, // only the triggered filters
     },
-    #[error("{allocator:?} did not find a valid slot for {item:?}")]
+    #[error("{allocator:?} did not find a valid slot for {item:?} ({diagnostics})")]
     FailedAllocation {
         allocator: String,  // FIXME: ideally, should be a clone of Allocator with its internal state
         item: Item,
+        diagnostics: Box<AllocDiagnostics>,
     },
+    #[error("No filter named {name:?}")]
+    UnknownFilter { name: String },
+    #[error("No reservation at slot {slot:?}")]
+    UnknownReservation { slot: Slot },
     #[error("No items found in slot {slot:?}")]
     NotFound { slot: Slot },
+    #[error("Slot {slot:?} is out of bounds for a warehouse with {layout}")]
+    InvalidSlot { slot: Slot, layout: WarehouseLayout },
+    #[error("{item:?} must cover from 1 to {zones} zones (one shelf)")]
+    InvalidItemSize { item: Item, zones: usize },
+    #[error("{item:?} does not fit at slot {slot:?}, it would go past the end of the shelf")]
+    SlotUnavailable { slot: Slot, item: Item },
+    #[error("Slot {slot:?} is already used by the item stored at {head:?}")]
+    SlotOccupied { slot: Slot, head: Slot },
+    #[error("Slot {slot:?} is held by the reservation at {head:?}")]
+    SlotReserved { slot: Slot, head: Slot },
+    #[error("Slot {slot:?} is free but zone {blocked_at:?}, also needed by the item, is in use")]
+    SlotBlocked { slot: Slot, blocked_at: Slot },
+    #[error("Slot {slot:?} is above row {max_row}, the highest allowed for this Fragile item")]
+    FragileRowExceeded { slot: Slot, max_row: usize },
+    #[error("Snapshot version {found} is not supported (expected {expected})")]
+    UnsupportedSnapshot { found: u32, expected: u32 },
+    #[error("Snapshot is corrupted: {reason}")]
+    CorruptedSnapshot { reason: String },
+    #[error("Journal is corrupted at line {line}: {reason}")]
+    CorruptedJournal { line: usize, reason: String },
+    #[error("Malformed record: {reason}")]
+    MalformedRecord { reason: String },
+    #[error("CSV error: {source}")]
+    Csv {
+        #[from]
+        source: csv::Error,
+    },
+    #[error("Failed to (de)serialize: {source}")]
+    Serialization {
+        #[from]
+        source: serde_json::Error,
+    },
+    #[error("I/O error: {source}")]
+    Io {
+        #[from]
+        source: std::io::Error,
+    },
 }
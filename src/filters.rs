@@ -1,14 +1,125 @@
-use crate::{Item, Quality, Slot};
-use std::collections::HashMap;
+use crate::rules::Rule;
+use crate::view::InventoryView;
+use crate::{Item, ManagerError, Quality, QualityKind, Slot};
+use chrono::{DateTime, Local, TimeDelta};
+use serde::{Deserialize, Serialize};
+use itertools::Itertools;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
 use std::fmt::{Debug, Display};
 
-// TODO: should be selectable AT RUN TIME
+/// Admission rule checked before an item is inserted into the warehouse.
 pub trait Filter: Display + Debug {
-    // Using &mut self to allow for internal states
-    fn filter(&self, item: &Item, inventory: &HashMap<Slot, Item>) -> bool;
+    // filters are only read while checking an item, state is updated through on_insert
+    /// Returns `true` if `item` is allowed in, given the current `inventory`.
+    ///
+    /// Prefer the indexed aggregates of [`InventoryView`] over scanning [`InventoryView::items`].
+    fn filter(&self, item: &Item, inventory: &dyn InventoryView) -> bool;
+
+    /// Human-readable explanation of why `item` was rejected (only called after `filter` failed).
+    fn reason(&self, item: &Item, _inventory: &dyn InventoryView) -> String {
+        format!("item {} is not allowed", item.id)
+    }
+
+    /// Whether a triggered filter rejects the item or only warns about it.
+    fn severity(&self) -> Severity {
+        Severity::Reject
+    }
+
+    /// Called after `item` was stored at `slot`, at time `now`.
+    /// Stateful filters (e.g. [`IntakeQuota`]) update their state here.
+    fn on_insert(&mut self, _slot: &Slot, _item: &Item, _now: DateTime<Local>) {}
+
+    /// Clears the internal state of a stateful filter.
+    fn reset(&mut self) {}
+
+    /// Serializable description of the filter, used by snapshots.
+    /// Filters returning `None` are not persisted.
+    fn to_config(&self) -> Option<FilterConfig> {
+        None
+    }
 }
 
-#[derive(Debug)]
+/// What happens when a filter is triggered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Severity {
+    /// The item is not inserted.
+    #[default]
+    Reject,
+    /// The item is inserted, the verdict is returned as a warning.
+    Warn,
+}
+
+/// Outcome of a filter that was triggered by an item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterVerdict {
+    pub filter: String, // name of the filter (its Display)
+    pub reason: String,
+}
+
+impl FilterVerdict {
+    pub fn new(filter: &dyn Filter, item: &Item, inventory: &dyn InventoryView) -> Self {
+        Self {
+            filter: filter.to_string(),
+            reason: filter.reason(item, inventory),
+        }
+    }
+}
+
+impl Display for FilterVerdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.filter, self.reason)
+    }
+}
+
+/// Serializable form of the filters provided by this crate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FilterConfig {
+    LimitOverSized(LimitOverSized),
+    LimitItemQuantity(LimitItemQuantity),
+    Quotas(Quotas),
+    MinShelfLife(MinShelfLife),
+    MaxOccupancy(MaxOccupancy),
+    IntakeQuota(IntakeQuota),
+    UniqueNames(UniqueNames),
+    BanQuality(BanQuality),
+    AllowIds(AllowIds),
+    Rule(Rule),
+    All(Vec<FilterConfig>),
+    Any(Vec<FilterConfig>),
+    Not(Box<FilterConfig>),
+    Warn(Box<FilterConfig>),
+}
+
+impl FilterConfig {
+    pub fn into_filter(self) -> Box<dyn Filter> {
+        match self {
+            FilterConfig::LimitOverSized(f) => Box::new(f),
+            FilterConfig::LimitItemQuantity(f) => Box::new(f),
+            FilterConfig::Quotas(f) => Box::new(f),
+            FilterConfig::MinShelfLife(f) => Box::new(f),
+            FilterConfig::MaxOccupancy(f) => Box::new(f),
+            FilterConfig::IntakeQuota(f) => Box::new(f),
+            FilterConfig::UniqueNames(f) => Box::new(f),
+            FilterConfig::BanQuality(f) => Box::new(f),
+            FilterConfig::AllowIds(f) => Box::new(f),
+            FilterConfig::Rule(f) => Box::new(f),
+            FilterConfig::All(configs) => Box::new(All::new(
+                configs.into_iter().map(|c| c.into_filter()).collect(),
+            )),
+            FilterConfig::Any(configs) => Box::new(Any::new(
+                configs.into_iter().map(|c| c.into_filter()).collect(),
+            )),
+            FilterConfig::Not(config) => Box::new(Not::new(config.into_filter())),
+            FilterConfig::Warn(config) => Box::new(Warn::new(config.into_filter())),
+        }
+    }
+}
+
+/// Allows at most `max_allowed` OverSized items in the warehouse.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LimitOverSized {
     max_allowed: usize,
 }
@@ -20,15 +131,23 @@ impl LimitOverSized {
 }
 
 impl Filter for LimitOverSized {
-    fn filter(&self, item: &Item, inventory: &HashMap<Slot, Item>) -> bool {
+    fn filter(&self, item: &Item, inventory: &dyn InventoryView) -> bool {
         if matches!(item.quality, Quality::Normal | Quality::Fragile { .. }) {
             return true;
         }
-        let count = inventory
-            .values()
-            .filter(|item| matches!(item.quality, Quality::OverSized { .. }))
-            .count();
-        count < self.max_allowed
+        inventory.count_quality(QualityKind::OverSized) < self.max_allowed
+    }
+
+    fn reason(&self, _item: &Item, inventory: &dyn InventoryView) -> String {
+        format!(
+            "{} OverSized items already stored, limit {}",
+            inventory.count_quality(QualityKind::OverSized),
+            self.max_allowed
+        )
+    }
+
+    fn to_config(&self) -> Option<FilterConfig> {
+        Some(FilterConfig::LimitOverSized(self.clone()))
     }
 }
 
@@ -38,9 +157,8 @@ impl Display for LimitOverSized {
     }
 }
 
-// TODO: Support a list of ids instead of a single Item id
-// TODO: Use reverse map to find IDs instead of searching (more efficient)
-#[derive(Debug)]
+/// Allows at most `max_allowed` units of the item with the given `id` (see [`Quotas`] for many ids).
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LimitItemQuantity {
     id: usize,
     max_allowed: usize,
@@ -53,16 +171,24 @@ impl LimitItemQuantity {
 }
 
 impl Filter for LimitItemQuantity {
-    fn filter(&self, item: &Item, inventory: &HashMap<Slot, Item>) -> bool {
+    fn filter(&self, item: &Item, inventory: &dyn InventoryView) -> bool {
         if item.id != self.id {
             return true;
         };
-        let total = inventory
-            .values()
-            .filter(|item| item.id == self.id)
-            .map(|item| item.quantity)
-            .sum::<usize>();
-        total + item.quantity <= self.max_allowed
+        inventory.units_id(self.id) + item.quantity <= self.max_allowed
+    }
+
+    fn reason(&self, item: &Item, inventory: &dyn InventoryView) -> String {
+        format!(
+            "item {} would reach {} units, limit {}",
+            self.id,
+            inventory.units_id(self.id) + item.quantity,
+            self.max_allowed
+        )
+    }
+
+    fn to_config(&self) -> Option<FilterConfig> {
+        Some(FilterConfig::LimitItemQuantity(self.clone()))
     }
 }
 
@@ -72,7 +198,357 @@ impl Display for LimitItemQuantity {
     }
 }
 
-#[derive(Debug)]
+/// Maximum units per id and per name, one instance covers a whole catalogue.
+///
+/// Items matching both an id and a name quota must respect both.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Quotas {
+    ids: BTreeMap<usize, usize>,    // id, max units
+    names: BTreeMap<String, usize>, // name, max units
+}
+
+/// One row of a quota file: exactly one of `id` and `name` is set.
+#[derive(Debug, Deserialize)]
+struct QuotaRecord {
+    id: Option<usize>,
+    name: Option<String>,
+    max_units: usize,
+}
+
+impl Quotas {
+    pub fn new(ids: BTreeMap<usize, usize>, names: BTreeMap<String, usize>) -> Self {
+        Quotas { ids, names }
+    }
+
+    /// Reads quotas from CSV with the columns `id,name,max_units`, e.g. `0,,50` or `,Flour,100`.
+    pub fn from_csv(reader: impl Read) -> Result<Self, ManagerError> {
+        let mut quotas = Quotas::default();
+        let mut reader = csv::Reader::from_reader(reader);
+        for (i, record) in reader.deserialize::<QuotaRecord>().enumerate() {
+            match record? {
+                QuotaRecord { id: Some(id), name: None, max_units } => {
+                    quotas.ids.insert(id, max_units);
+                }
+                QuotaRecord { id: None, name: Some(name), max_units } => {
+                    quotas.names.insert(name, max_units);
+                }
+                _ => {
+                    return Err(ManagerError::MalformedRecord {
+                        reason: format!("line {}: set either an id or a name", i + 2),
+                    });
+                }
+            }
+        }
+        Ok(quotas)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ManagerError> {
+        Self::from_csv(File::open(path)?)
+    }
+
+    fn exceeded(&self, item: &Item, inventory: &dyn InventoryView) -> Vec<String> {
+        // one message per quota the item would break
+        let by_id = self.ids.get(&item.id).and_then(|max| {
+            let units = inventory.units_id(item.id) + item.quantity;
+            (units > *max).then(|| format!("item {} would reach {} units, limit {}", item.id, units, max))
+        });
+        let by_name = self.names.get(&item.name).and_then(|max| {
+            let units = inventory.units_name(&item.name) + item.quantity;
+            (units > *max).then(|| format!("{:?} would reach {} units, limit {}", item.name, units, max))
+        });
+        by_id.into_iter().chain(by_name).collect()
+    }
+}
+
+impl Filter for Quotas {
+    fn filter(&self, item: &Item, inventory: &dyn InventoryView) -> bool {
+        self.exceeded(item, inventory).is_empty()
+    }
+
+    fn reason(&self, item: &Item, inventory: &dyn InventoryView) -> String {
+        self.exceeded(item, inventory).join("; ")
+    }
+
+    fn to_config(&self) -> Option<FilterConfig> {
+        Some(FilterConfig::Quotas(self.clone()))
+    }
+}
+
+impl Display for Quotas {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Quotas({} ids, {} names)", self.ids.len(), self.names.len())
+    }
+}
+
+/// Rejects Fragile items that are expired or expire in less than `min_days` days.
+///
+/// The remaining shelf life is measured from [`InventoryView::now`], i.e. the manager clock.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinShelfLife {
+    min_days: i64,
+}
+
+impl MinShelfLife {
+    pub fn new(min_days: i64) -> Self {
+        MinShelfLife { min_days }
+    }
+
+    fn remaining(item: &Item, inventory: &dyn InventoryView) -> Option<TimeDelta> {
+        match item.quality {
+            Quality::Fragile {
+                expiration_date, ..
+            } => Some(expiration_date - inventory.now()),
+            _ => None,
+        }
+    }
+
+    fn duration(delta: TimeDelta) -> String {
+        // in hours below a day, so that a recent expiration does not read "0 days"
+        let plural = |n: i64| if n == 1 { "" } else { "s" };
+        match (delta.num_days(), delta.num_hours()) {
+            (0, 0) => "less than an hour".to_string(),
+            (0, hours) => format!("{} hour{}", hours, plural(hours)),
+            (days, _) => format!("{} day{}", days, plural(days)),
+        }
+    }
+}
+
+impl Filter for MinShelfLife {
+    fn filter(&self, item: &Item, inventory: &dyn InventoryView) -> bool {
+        match Self::remaining(item, inventory) {
+            Some(remaining) => {
+                // a shelf life too long for a TimeDelta can never be met (or is always met if negative)
+                let enough = TimeDelta::try_days(self.min_days).map_or(self.min_days < 0, |min| remaining >= min);
+                remaining > TimeDelta::zero() && enough
+            }
+            None => true, // only Fragile items expire
+        }
+    }
+
+    fn reason(&self, item: &Item, inventory: &dyn InventoryView) -> String {
+        let remaining = Self::remaining(item, inventory).unwrap_or_default();
+        if remaining <= TimeDelta::zero() {
+            format!("item {} expired {} ago", item.id, Self::duration(-remaining))
+        } else {
+            format!(
+                "item {} expires in {}, at least {} days required",
+                item.id,
+                Self::duration(remaining),
+                self.min_days
+            )
+        }
+    }
+
+    fn to_config(&self) -> Option<FilterConfig> {
+        Some(FilterConfig::MinShelfLife(self.clone()))
+    }
+}
+
+impl Display for MinShelfLife {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MinShelfLife({} days)", self.min_days)
+    }
+}
+
+/// Keeps headroom for emergency deliveries: rejects items that would bring the occupancy
+/// (in zones, OverSized items count every zone they cover) above `max_percent`.
+///
+/// Items with an exempt id or quality are always allowed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaxOccupancy {
+    max_percent: usize,
+    exempt_ids: BTreeSet<usize>,
+    exempt_qualities: BTreeSet<QualityKind>,
+}
+
+impl MaxOccupancy {
+    pub fn new(
+        max_percent: usize,
+        exempt_ids: impl IntoIterator<Item = usize>,
+        exempt_qualities: impl IntoIterator<Item = QualityKind>,
+    ) -> Self {
+        MaxOccupancy {
+            max_percent,
+            exempt_ids: exempt_ids.into_iter().collect(),
+            exempt_qualities: exempt_qualities.into_iter().collect(),
+        }
+    }
+}
+
+impl Filter for MaxOccupancy {
+    fn filter(&self, item: &Item, inventory: &dyn InventoryView) -> bool {
+        if self.exempt_ids.contains(&item.id) || self.exempt_qualities.contains(&item.quality.kind()) {
+            return true;
+        }
+        // compare in zones to avoid rounding: used / capacity <= max_percent / 100
+        // (saturating, a huge OverSized size must be rejected rather than wrap around)
+        let used = inventory.occupied_zones().saturating_add(item.size());
+        used.saturating_mul(100) <= self.max_percent.saturating_mul(inventory.layout().capacity())
+    }
+
+    fn reason(&self, item: &Item, inventory: &dyn InventoryView) -> String {
+        let used = inventory.occupied_zones().saturating_add(item.size());
+        let capacity = inventory.layout().capacity();
+        format!(
+            "occupancy would reach {}/{} zones ({}%), limit {}%",
+            used,
+            capacity,
+            used.saturating_mul(100) / capacity.max(1),
+            self.max_percent
+        )
+    }
+
+    fn to_config(&self) -> Option<FilterConfig> {
+        Some(FilterConfig::MaxOccupancy(self.clone()))
+    }
+}
+
+impl Display for MaxOccupancy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MaxOccupancy({}%)", self.max_percent)
+    }
+}
+
+/// Items counted by an [`IntakeQuota`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IntakeScope {
+    All,
+    Id(usize),
+    Quality(QualityKind),
+}
+
+impl IntakeScope {
+    fn matches(&self, item: &Item) -> bool {
+        match self {
+            IntakeScope::All => true,
+            IntakeScope::Id(id) => item.id == *id,
+            IntakeScope::Quality(kind) => item.quality.kind() == *kind,
+        }
+    }
+}
+
+impl Display for IntakeScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntakeScope::All => write!(f, "all"),
+            IntakeScope::Id(id) => write!(f, "id {}", id),
+            IntakeScope::Quality(kind) => write!(f, "{}", kind),
+        }
+    }
+}
+
+/// Rate limit on received pallets: at most `max_items` items in `scope` over a sliding window
+/// (e.g. 20 OverSized pallets per day).
+///
+/// Remembers when matching items were stored, this state is persisted with the snapshots.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntakeQuota {
+    scope: IntakeScope,
+    max_items: usize,
+    window_secs: i64,
+    received: VecDeque<DateTime<Local>>, // insertion times inside the window, sorted, oldest first
+}
+
+impl IntakeQuota {
+    pub fn new(scope: IntakeScope, max_items: usize, window: TimeDelta) -> Self {
+        IntakeQuota {
+            scope,
+            max_items,
+            window_secs: window.num_seconds(),
+            received: VecDeque::new(),
+        }
+    }
+
+    fn received_since(&self, now: DateTime<Local>) -> usize {
+        // not a take_while: items imported with an old timestamp may still be in the list
+        match now.checked_sub_signed(TimeDelta::seconds(self.window_secs)) {
+            Some(start) => self.received.iter().filter(|t| **t > start).count(),
+            None => self.received.len(), // the window starts before any representable date
+        }
+    }
+
+    fn window(&self) -> String {
+        match self.window_secs {
+            secs if secs % 86_400 == 0 => format!("{} day(s)", secs / 86_400),
+            secs if secs % 3_600 == 0 => format!("{} hour(s)", secs / 3_600),
+            secs => format!("{} second(s)", secs),
+        }
+    }
+}
+
+impl Filter for IntakeQuota {
+    fn filter(&self, item: &Item, inventory: &dyn InventoryView) -> bool {
+        !self.scope.matches(item) || self.received_since(inventory.now()) < self.max_items
+    }
+
+    fn reason(&self, _item: &Item, inventory: &dyn InventoryView) -> String {
+        format!(
+            "{} pallets ({}) received in the last {}, limit {}",
+            self.received_since(inventory.now()),
+            self.scope,
+            self.window(),
+            self.max_items
+        )
+    }
+
+    fn on_insert(&mut self, _slot: &Slot, item: &Item, now: DateTime<Local>) {
+        if !self.scope.matches(item) {
+            return;
+        }
+        // `now` is the item timestamp, which is older than the last one for imported items
+        let index = self.received.partition_point(|t| *t <= now);
+        self.received.insert(index, now);
+        // forget what fell out of the window
+        let latest = *self.received.back().unwrap_or(&now);
+        let Some(start) = latest.checked_sub_signed(TimeDelta::seconds(self.window_secs)) else {
+            return;
+        };
+        while self.received.front().is_some_and(|t| *t <= start) {
+            self.received.pop_front();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.received.clear();
+    }
+
+    fn to_config(&self) -> Option<FilterConfig> {
+        Some(FilterConfig::IntakeQuota(self.clone()))
+    }
+}
+
+impl Display for IntakeQuota {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "IntakeQuota({}, {} per {})", self.scope, self.max_items, self.window())
+    }
+}
+
+/// Triggered by items whose name is already used by a different id (usually wrapped in [`Warn`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UniqueNames;
+
+impl Filter for UniqueNames {
+    fn filter(&self, item: &Item, inventory: &dyn InventoryView) -> bool {
+        inventory.count_name(&item.name) == inventory.count_name_id(&item.name, item.id)
+    }
+
+    fn reason(&self, item: &Item, _inventory: &dyn InventoryView) -> String {
+        format!("{:?} is already stored with a different id than {}", item.name, item.id)
+    }
+
+    fn to_config(&self) -> Option<FilterConfig> {
+        Some(FilterConfig::UniqueNames(self.clone()))
+    }
+}
+
+impl Display for UniqueNames {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UniqueNames")
+    }
+}
+
+/// Rejects every item with the given quality (including its parameters).
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BanQuality {
     quality: Quality,
 }
@@ -84,12 +560,20 @@ impl BanQuality {
 }
 
 impl Filter for BanQuality {
-    fn filter(&self, item: &Item, inventory: &HashMap<Slot, Item>) -> bool {
+    fn filter(&self, item: &Item, _inventory: &dyn InventoryView) -> bool {
         match (&self.quality, &item.quality) {
             (q1, q2) if q1 == q2 => false,
             (_, _) => true,
         }
     }
+
+    fn reason(&self, _item: &Item, _inventory: &dyn InventoryView) -> String {
+        format!("{:?} items are banned", self.quality)
+    }
+
+    fn to_config(&self) -> Option<FilterConfig> {
+        Some(FilterConfig::BanQuality(self.clone()))
+    }
 }
 
 impl Display for BanQuality {
@@ -98,23 +582,251 @@ impl Display for BanQuality {
     }
 }
 
+/// Allows only items whose id is in `ids` (e.g. a priority list, usually combined with [`Any`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllowIds {
+    ids: BTreeSet<usize>,
+}
+
+impl AllowIds {
+    pub fn new(ids: impl IntoIterator<Item = usize>) -> Self {
+        AllowIds {
+            ids: ids.into_iter().collect(),
+        }
+    }
+}
+
+impl Filter for AllowIds {
+    fn filter(&self, item: &Item, _inventory: &dyn InventoryView) -> bool {
+        self.ids.contains(&item.id)
+    }
+
+    fn reason(&self, item: &Item, _inventory: &dyn InventoryView) -> String {
+        format!("item {} is not in {:?}", item.id, self.ids)
+    }
+
+    fn to_config(&self) -> Option<FilterConfig> {
+        Some(FilterConfig::AllowIds(self.clone()))
+    }
+}
+
+impl Display for AllowIds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AllowIds({:?})", self.ids)
+    }
+}
+
+// Combinators: build admission rules out of other filters (they can be nested)
+
+fn children_configs(filters: &[Box<dyn Filter>]) -> Option<Vec<FilterConfig>> {
+    // a combinator can only be persisted if every child can
+    filters.iter().map(|f| f.to_config()).collect()
+}
+
+/// Allows an item only if every inner filter allows it (AND).
+#[derive(Debug)]
+pub struct All {
+    filters: Vec<Box<dyn Filter>>,
+}
+
+impl All {
+    pub fn new(filters: Vec<Box<dyn Filter>>) -> Self {
+        All { filters }
+    }
+}
+
+impl Filter for All {
+    fn filter(&self, item: &Item, inventory: &dyn InventoryView) -> bool {
+        self.filters.iter().all(|f| f.filter(item, inventory))
+    }
+
+    fn reason(&self, item: &Item, inventory: &dyn InventoryView) -> String {
+        self.filters
+            .iter()
+            .filter(|f| !f.filter(item, inventory))
+            .map(|f| FilterVerdict::new(f.as_ref(), item, inventory))
+            .join("; ")
+    }
+
+    fn on_insert(&mut self, slot: &Slot, item: &Item, now: DateTime<Local>) {
+        self.filters.iter_mut().for_each(|f| f.on_insert(slot, item, now));
+    }
+
+    fn reset(&mut self) {
+        self.filters.iter_mut().for_each(|f| f.reset());
+    }
+
+    fn to_config(&self) -> Option<FilterConfig> {
+        children_configs(&self.filters).map(FilterConfig::All)
+    }
+}
+
+impl Display for All {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "All({})", self.filters.iter().join(", "))
+    }
+}
+
+/// Allows an item if at least one inner filter allows it (OR).
+#[derive(Debug)]
+pub struct Any {
+    filters: Vec<Box<dyn Filter>>,
+}
+
+impl Any {
+    pub fn new(filters: Vec<Box<dyn Filter>>) -> Self {
+        Any { filters }
+    }
+}
+
+impl Filter for Any {
+    fn filter(&self, item: &Item, inventory: &dyn InventoryView) -> bool {
+        self.filters.iter().any(|f| f.filter(item, inventory))
+    }
+
+    fn reason(&self, item: &Item, inventory: &dyn InventoryView) -> String {
+        // every inner filter rejected the item
+        let reasons = self
+            .filters
+            .iter()
+            .map(|f| FilterVerdict::new(f.as_ref(), item, inventory))
+            .join("; ");
+        format!("none of the alternatives allows it ({})", reasons)
+    }
+
+    fn on_insert(&mut self, slot: &Slot, item: &Item, now: DateTime<Local>) {
+        self.filters.iter_mut().for_each(|f| f.on_insert(slot, item, now));
+    }
+
+    fn reset(&mut self) {
+        self.filters.iter_mut().for_each(|f| f.reset());
+    }
+
+    fn to_config(&self) -> Option<FilterConfig> {
+        children_configs(&self.filters).map(FilterConfig::Any)
+    }
+}
+
+impl Display for Any {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Any({})", self.filters.iter().join(", "))
+    }
+}
+
+/// Allows an item only if the inner filter rejects it (NOT).
+#[derive(Debug)]
+pub struct Not {
+    filter: Box<dyn Filter>,
+}
+
+impl Not {
+    pub fn new(filter: Box<dyn Filter>) -> Self {
+        Not { filter }
+    }
+}
+
+impl Filter for Not {
+    fn filter(&self, item: &Item, inventory: &dyn InventoryView) -> bool {
+        !self.filter.filter(item, inventory)
+    }
+
+    fn reason(&self, item: &Item, _inventory: &dyn InventoryView) -> String {
+        format!("item {} is allowed by {}", item.id, self.filter)
+    }
+
+    fn on_insert(&mut self, slot: &Slot, item: &Item, now: DateTime<Local>) {
+        self.filter.on_insert(slot, item, now);
+    }
+
+    fn reset(&mut self) {
+        self.filter.reset();
+    }
+
+    fn to_config(&self) -> Option<FilterConfig> {
+        self.filter
+            .to_config()
+            .map(|config| FilterConfig::Not(Box::new(config)))
+    }
+}
+
+impl Display for Not {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Not({})", self.filter)
+    }
+}
+
+/// Turns the inner filter into an advisory one: the item is inserted and the verdict is
+/// returned as a warning. Only meaningful for filters given directly to the [`Manager`](crate::Manager).
+#[derive(Debug)]
+pub struct Warn {
+    filter: Box<dyn Filter>,
+}
+
+impl Warn {
+    pub fn new(filter: Box<dyn Filter>) -> Self {
+        Warn { filter }
+    }
+}
+
+impl Filter for Warn {
+    fn filter(&self, item: &Item, inventory: &dyn InventoryView) -> bool {
+        self.filter.filter(item, inventory)
+    }
+
+    fn reason(&self, item: &Item, inventory: &dyn InventoryView) -> String {
+        self.filter.reason(item, inventory)
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warn
+    }
+
+    fn on_insert(&mut self, slot: &Slot, item: &Item, now: DateTime<Local>) {
+        self.filter.on_insert(slot, item, now);
+    }
+
+    fn reset(&mut self) {
+        self.filter.reset();
+    }
+
+    fn to_config(&self) -> Option<FilterConfig> {
+        self.filter
+            .to_config()
+            .map(|config| FilterConfig::Warn(Box::new(config)))
+    }
+}
+
+impl Display for Warn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Warn({})", self.filter)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{BanQuality, Filter, LimitItemQuantity, LimitOverSized};
-    use crate::allocators::RoundRobinAllocator;
+    use super::{
+        All, AllowIds, Any, BanQuality, Filter, FilterConfig, FilterVerdict, IntakeQuota,
+        IntakeScope, LimitItemQuantity, LimitOverSized, MaxOccupancy, MinShelfLife, Not, Quotas,
+        Severity, UniqueNames, Warn,
+    };
+    use crate::allocators::DefaultAllocator;
+    use crate::csv_io::ImportMode;
     use crate::errors::ManagerError;
-    use crate::{Item, MAX_INVENTORY_SIZE, Manager, Quality, Slot};
-    use chrono::{Local, NaiveDateTime, TimeZone};
+    use crate::{FixedClock, Item, Manager, Quality, QualityKind, WarehouseLayout};
+    use chrono::{Local, TimeDelta, TimeZone};
+
     #[test]
     fn test_filters() {
-        let mut filters = Vec::<Box<dyn Filter>>::new();
-        filters.push(Box::from(LimitOverSized::new(1)));
-        filters.push(Box::from(LimitItemQuantity::new(2, 50)));
-        filters.push(Box::from(BanQuality::new(Quality::OverSized {
-            size: MAX_INVENTORY_SIZE,
-        })));
+        let layout = WarehouseLayout::new(3, 3, 3);
+        let filters: Vec<Box<dyn Filter>> = vec![
+            Box::from(LimitOverSized::new(1)),
+            Box::from(LimitItemQuantity::new(2, 50)),
+            Box::from(BanQuality::new(Quality::OverSized {
+                size: layout.zones,
+            })),
+        ];
 
-        let mut manager = Manager::new(RoundRobinAllocator::default(), filters);
+        let mut manager = Manager::new(layout, DefaultAllocator::default(), filters);
 
         // LimitOverSized
         let allowed_item = Item::new(0, "A", 1, Quality::OverSized { size: 1 });
@@ -129,7 +841,7 @@ mod tests {
             ManagerError::FilteredItem { .. }
         )));
 
-        manager.remove_item(0, 0, 0);
+        manager.remove_item(0, 0, 0).unwrap();
 
         // LimitItemQuantity
         let item = Item::new(2, "C", 10, Quality::Normal);
@@ -146,11 +858,278 @@ mod tests {
         )));
 
         // BanQuality
-        let forbidden_item = Item::new(3, "D", 1, Quality::OverSized { size: MAX_INVENTORY_SIZE });
+        let forbidden_item = Item::new(3, "D", 1, Quality::OverSized { size: layout.zones });
         let result = manager.insert_item(forbidden_item.clone());
         assert!(result.is_err_and(|err| matches!(
             err,
             ManagerError::FilteredItem { .. }
         )));
     }
+
+    #[test]
+    fn test_filter_verdicts() {
+        let layout = WarehouseLayout::new(3, 3, 3);
+        let filters: Vec<Box<dyn Filter>> = vec![
+            Box::from(LimitOverSized::new(1)),
+            Box::from(LimitItemQuantity::new(0, 50)),
+            Box::from(BanQuality::new(Quality::OverSized { size: 3 })),
+        ];
+        let mut manager = Manager::new(layout, DefaultAllocator::default(), filters);
+        manager.insert_item(Item::new(0, "A", 40, Quality::OverSized { size: 2 })).unwrap();
+
+        // only the triggered filter is reported
+        let result = manager.insert_item(Item::new(0, "A", 20, Quality::Normal));
+        assert!(result.is_err_and(|err| match err {
+            ManagerError::FilteredItem { filters, .. } => filters == vec![FilterVerdict {
+                filter: "LimitItemQuantity(0, 50)".to_string(),
+                reason: "item 0 would reach 60 units, limit 50".to_string(),
+            }],
+            _ => false,
+        }));
+
+        // every triggered filter is reported
+        let verdicts = manager
+            .is_allowed_by_filters(&Item::new(1, "B", 1, Quality::OverSized { size: 3 }))
+            .unwrap_err();
+        let names: Vec<&str> = verdicts.iter().map(|v| v.filter.as_str()).collect();
+        assert_eq!(names, vec!["LimitOverSized(1)", "BanQuality(OverSized)"]);
+        assert_eq!(verdicts[0].reason, "1 OverSized items already stored, limit 1");
+
+        assert!(manager.is_allowed_by_filters(&Item::new(1, "B", 1, Quality::Normal)).is_ok());
+    }
+
+    #[test]
+    fn test_filter_combinators() {
+        // allow OverSized only if fewer than 2 are stored OR the item id is in the priority list,
+        // and never allow Normal items of id 9
+        let rule = All::new(vec![
+            Box::from(Any::new(vec![
+                Box::from(LimitOverSized::new(2)),
+                Box::from(AllowIds::new([0, 4, 7])),
+            ])),
+            Box::from(Not::new(Box::from(All::new(vec![
+                Box::from(AllowIds::new([9])),
+                Box::from(BanQuality::new(Quality::OverSized { size: 1 })),
+            ])))),
+        ]);
+        assert_eq!(
+            rule.to_string(),
+            "All(Any(LimitOverSized(2), AllowIds({0, 4, 7})), Not(All(AllowIds({9}), BanQuality(OverSized))))"
+        );
+
+        // combinators are persisted as a tree
+        let config = rule.to_config().unwrap();
+        assert!(matches!(&config, FilterConfig::All(children) if children.len() == 2));
+        assert_eq!(config.into_filter().to_string(), rule.to_string());
+
+        let layout = WarehouseLayout::new(3, 3, 3);
+        let mut manager = Manager::new(layout, DefaultAllocator::default(), vec![Box::from(rule)]);
+        let oversized = |id| Item::new(id, "A", 1, Quality::OverSized { size: 1 });
+
+        assert!(manager.insert_item(oversized(9)).is_ok());
+        let result = manager.insert_item(Item::new(9, "B", 1, Quality::Normal));
+        assert!(result.is_err_and(|err| matches!(err, ManagerError::FilteredItem { .. })));
+
+        assert!(manager.insert_item(oversized(1)).is_ok());
+        let result = manager.insert_item(oversized(3));
+        assert!(result.is_err_and(|err| match err {
+            ManagerError::FilteredItem { filters, .. } => {
+                filters.len() == 1 && filters[0].reason.starts_with("Any(")
+            }
+            _ => false,
+        }));
+        assert!(manager.insert_item(oversized(4)).is_ok()); // priority list
+    }
+
+    #[test]
+    fn test_quotas() {
+        let csv = "\
+id,name,max_units
+0,,50
+1,,10
+,Flour,60
+";
+        let quotas = Quotas::from_csv(csv.as_bytes()).unwrap();
+        assert_eq!(quotas.to_string(), "Quotas(2 ids, 1 names)");
+        let invalid = "id,name,max_units\n0,Flour,50\n";
+        assert!(Quotas::from_csv(invalid.as_bytes()).is_err_and(|err| matches!(
+            err,
+            ManagerError::MalformedRecord { .. }
+        )));
+
+        let layout = WarehouseLayout::new(3, 3, 3);
+        let mut manager = Manager::new(layout, DefaultAllocator::default(), vec![Box::from(quotas)]);
+        manager.insert_item(Item::new(0, "Flour", 40, Quality::Normal)).unwrap();
+        manager.insert_item(Item::new(1, "Wood", 10, Quality::Normal)).unwrap();
+        manager.insert_item(Item::new(2, "Salt", 500, Quality::Normal)).unwrap(); // no quota
+
+        let result = manager.insert_item(Item::new(1, "Wood", 1, Quality::Normal));
+        assert!(result.is_err_and(|err| matches!(err, ManagerError::FilteredItem { .. })));
+
+        // id 3 has no quota, but the name is shared with id 0
+        manager.insert_item(Item::new(3, "Flour", 20, Quality::Normal)).unwrap();
+        let verdicts = manager
+            .is_allowed_by_filters(&Item::new(0, "Flour", 15, Quality::Normal))
+            .unwrap_err();
+        assert_eq!(
+            verdicts[0].reason,
+            "item 0 would reach 55 units, limit 50; \"Flour\" would reach 75 units, limit 60"
+        );
+    }
+
+    #[test]
+    fn test_min_shelf_life() {
+        let now = Local.with_ymd_and_hms(2024, 3, 10, 12, 0, 0).unwrap();
+        let fragile = |id, days: i64| {
+            let expiration_date = now + TimeDelta::days(days) + TimeDelta::hours(1);
+            Item::new(id, "Milk", 1, Quality::Fragile { expiration_date, max_row: 2 })
+        };
+        let filters: Vec<Box<dyn Filter>> = vec![Box::from(MinShelfLife::new(3))];
+        let mut manager = Manager::new(WarehouseLayout::new(3, 3, 3), DefaultAllocator::default(), filters);
+        manager.set_clock(Box::new(FixedClock::new(now)));
+
+        assert!(manager.insert_item(fragile(0, 3)).is_ok());
+        assert!(manager.insert_item(Item::new(1, "Salt", 1, Quality::Normal)).is_ok());
+        let verdicts = manager.is_allowed_by_filters(&fragile(2, 2)).unwrap_err();
+        assert_eq!(verdicts[0].reason, "item 2 expires in 2 days, at least 3 days required");
+        let verdicts = manager.is_allowed_by_filters(&fragile(3, -5)).unwrap_err();
+        assert_eq!(verdicts[0].reason, "item 3 expired 4 days ago");
+        let verdicts = manager.is_allowed_by_filters(&fragile(3, -1)).unwrap_err();
+        assert_eq!(verdicts[0].reason, "item 3 expired 23 hours ago");
+        let verdicts = manager.is_allowed_by_filters(&fragile(3, 0)).unwrap_err();
+        assert_eq!(verdicts[0].reason, "item 3 expires in 1 hour, at least 3 days required");
+
+        // out-of-range shelf lives do not overflow
+        let filter = MinShelfLife::new(i64::MAX);
+        assert!(!filter.filter(&fragile(4, 3), &manager));
+        let filter = MinShelfLife::new(i64::MIN);
+        assert!(filter.filter(&fragile(4, 3), &manager));
+
+        // the same item is accepted earlier in time
+        manager.set_clock(Box::new(FixedClock::new(now - TimeDelta::days(1))));
+        let slot = manager.insert_item(fragile(2, 2)).unwrap().slot;
+        let item = manager.get_item(slot.row(), slot.shelf(), slot.zone()).unwrap().unwrap();
+        assert_eq!(item.timestamp(), Some(now - TimeDelta::days(1))); // timestamps follow the clock
+    }
+
+    #[test]
+    fn test_max_occupancy() {
+        // 2 x 2 x 3 = 12 zones, keep at least 3 free
+        let layout = WarehouseLayout::new(2, 2, 3);
+        let filter = MaxOccupancy::new(75, [7], [QualityKind::Fragile]);
+        let mut manager = Manager::new(layout, DefaultAllocator::default(), vec![Box::from(filter)]);
+
+        manager.insert_item(Item::new(0, "Wood", 1, Quality::OverSized { size: 3 })).unwrap();
+        manager.insert_item(Item::new(0, "Wood", 1, Quality::OverSized { size: 3 })).unwrap();
+        manager.insert_item(Item::new(1, "Salt", 1, Quality::Normal)).unwrap();
+        manager.insert_item(Item::new(1, "Salt", 1, Quality::Normal)).unwrap();
+        manager.insert_item(Item::new(1, "Salt", 1, Quality::Normal)).unwrap(); // 9/12 zones
+
+        let verdicts = manager
+            .is_allowed_by_filters(&Item::new(1, "Salt", 1, Quality::Normal))
+            .unwrap_err();
+        assert_eq!(verdicts[0].reason, "occupancy would reach 10/12 zones (83%), limit 75%");
+        let huge = Item::new(1, "Beam", 1, Quality::OverSized { size: usize::MAX });
+        assert!(manager.is_allowed_by_filters(&huge).is_err());
+
+        // exemptions
+        assert!(manager.insert_item(Item::new(7, "Oil", 1, Quality::Normal)).is_ok());
+        let expiration_date = chrono::Local::now() + TimeDelta::days(10);
+        let fragile = Item::new(2, "Milk", 1, Quality::Fragile { expiration_date, max_row: 1 });
+        assert!(manager.insert_item(fragile).is_ok());
+
+        // removing frees headroom
+        let slot = manager.find_id(0).unwrap()[0];
+        manager.remove_item(slot.row(), slot.shelf(), slot.zone()).unwrap(); // 3 zones
+        assert!(manager.insert_item(Item::new(1, "Salt", 1, Quality::Normal)).is_ok());
+        assert!(manager.insert_item(Item::new(1, "Salt", 1, Quality::Normal)).is_err());
+    }
+
+    #[test]
+    fn test_intake_quota() {
+        let now = Local.with_ymd_and_hms(2024, 3, 10, 12, 0, 0).unwrap();
+        let oversized = || Item::new(0, "Wood", 1, Quality::OverSized { size: 1 });
+        let quota = IntakeQuota::new(IntakeScope::Quality(QualityKind::OverSized), 2, TimeDelta::days(1));
+        assert_eq!(quota.to_string(), "IntakeQuota(OverSized, 2 per 1 day(s))");
+        // state updates go through combinators
+        let filters: Vec<Box<dyn Filter>> = vec![Box::from(All::new(vec![Box::from(quota)]))];
+        let mut manager = Manager::new(WarehouseLayout::new(3, 3, 3), DefaultAllocator::default(), filters);
+
+        manager.set_clock(Box::new(FixedClock::new(now)));
+        manager.insert_item(oversized()).unwrap();
+        manager.set_clock(Box::new(FixedClock::new(now + TimeDelta::hours(12))));
+        manager.insert_item(oversized()).unwrap();
+        manager.insert_item(Item::new(1, "Salt", 1, Quality::Normal)).unwrap(); // out of scope
+        let verdicts = manager.is_allowed_by_filters(&oversized()).unwrap_err();
+        assert!(verdicts[0].reason.contains("2 pallets (OverSized) received in the last 1 day(s), limit 2"));
+
+        // the first pallet leaves the window, removals do not matter
+        manager.set_clock(Box::new(FixedClock::new(now + TimeDelta::hours(25))));
+        manager.insert_item(oversized()).unwrap();
+        assert!(manager.insert_item(oversized()).is_err());
+
+        // the window is persisted
+        let mut loaded = Manager::<DefaultAllocator>::from_json(&manager.to_json().unwrap()).unwrap();
+        loaded.set_clock(Box::new(FixedClock::new(now + TimeDelta::hours(25))));
+        assert!(loaded.insert_item(oversized()).is_err());
+
+        loaded.reset_filters();
+        assert!(loaded.insert_item(oversized()).is_ok());
+    }
+
+    #[test]
+    fn test_intake_quota_backdated_items() {
+        let now = Local.with_ymd_and_hms(2024, 3, 10, 12, 0, 0).unwrap();
+        let filters: Vec<Box<dyn Filter>> = vec![Box::from(IntakeQuota::new(IntakeScope::Id(0), 2, TimeDelta::days(1)))];
+        let mut manager = Manager::new(WarehouseLayout::new(3, 3, 3), DefaultAllocator::default(), filters);
+        manager.set_clock(Box::new(FixedClock::new(now)));
+        manager.insert_item(Item::new(0, "Wood", 1, Quality::Normal)).unwrap();
+
+        // an imported pallet keeps its (old) timestamp, it does not hide the pallets received today
+        let csv = "\
+row,shelf,zone,id,name,quantity,quality,size,max_row,timestamp,expiration_date
+2,2,2,0,Wood,1,Normal,,,2020-01-01 08:00:00,
+";
+        let report = manager.import_csv(csv.as_bytes(), ImportMode::AtSlots);
+        assert_eq!(report.imported.len(), 1);
+        manager.insert_item(Item::new(0, "Wood", 1, Quality::Normal)).unwrap();
+        let verdicts = manager.is_allowed_by_filters(&Item::new(0, "Wood", 1, Quality::Normal)).unwrap_err();
+        assert!(verdicts[0].reason.contains("2 pallets (id 0) received in the last 1 day(s), limit 2"));
+    }
+
+    #[test]
+    fn test_soft_filters() {
+        let warn = Warn::new(Box::from(UniqueNames));
+        assert_eq!(warn.severity(), Severity::Warn);
+        let config = warn.to_config().unwrap();
+        assert!(matches!(&config, FilterConfig::Warn(inner) if matches!(**inner, FilterConfig::UniqueNames(_))));
+        assert_eq!(config.into_filter().severity(), Severity::Warn);
+
+        let filters: Vec<Box<dyn Filter>> = vec![Box::from(warn), Box::from(LimitOverSized::new(1))];
+        let mut manager = Manager::new(WarehouseLayout::new(3, 3, 3), DefaultAllocator::default(), filters);
+
+        let insertion = manager.insert_item(Item::new(0, "Flour", 1, Quality::Normal)).unwrap();
+        assert!(insertion.warnings.is_empty());
+        assert!(manager.insert_item(Item::new(0, "Flour", 1, Quality::Normal)).unwrap().warnings.is_empty());
+
+        // warned, but inserted
+        let insertion = manager.insert_item(Item::new(1, "Flour", 1, Quality::OverSized { size: 1 })).unwrap();
+        assert_eq!(
+            insertion.warnings,
+            vec![FilterVerdict {
+                filter: "Warn(UniqueNames)".to_string(),
+                reason: "\"Flour\" is already stored with a different id than 1".to_string(),
+            }]
+        );
+        assert_eq!(manager.count_name("Flour"), 3);
+
+        // rejections only report the rejecting filters
+        let result = manager.insert_item(Item::new(2, "Flour", 1, Quality::OverSized { size: 1 }));
+        assert!(result.is_err_and(|err| match err {
+            ManagerError::FilteredItem { filters, .. } => {
+                filters.len() == 1 && filters[0].filter == "LimitOverSized(1)"
+            }
+            _ => false,
+        }));
+    }
 }
@@ -0,0 +1,216 @@
+use crate::{AllocStrategy, Item, Manager, ManagerError, Slot};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Operation recorded in the journal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum JournalEvent {
+    /// `item` (with its timestamp) was stored at `slot`.
+    Insert { slot: Slot, item: Item },
+    /// `item` was removed from `slot` (the first zone it occupied).
+    Remove { slot: Slot, item: Item },
+}
+
+/// A single line of the journal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalRecord {
+    pub seq: u64,
+    pub timestamp: DateTime<Local>,
+    pub event: JournalEvent,
+}
+
+/// Append-only log of every insertion and removal, stored as JSON lines.
+///
+/// Each record has a sequence number, so replaying it on top of a snapshot only applies
+/// the events that happened after the snapshot was taken.
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    file: File,
+}
+
+impl Journal {
+    /// Opens (or creates) the journal at `path` for appending.
+    ///
+    /// A truncated last record, left behind by a crash, is discarded so that new records
+    /// start on a fresh line.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ManagerError> {
+        let path = path.as_ref().to_path_buf();
+        if let Ok(contents) = fs::read(&path) {
+            let valid_len = contents
+                .iter()
+                .rposition(|b| *b == b'\n')
+                .map_or(0, |pos| pos + 1);
+            if valid_len < contents.len() {
+                OpenOptions::new()
+                    .write(true)
+                    .open(&path)?
+                    .set_len(valid_len as u64)?;
+            }
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self { path, file })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends a record and flushes it to disk.
+    pub fn append(&mut self, record: &JournalRecord) -> Result<(), ManagerError> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes())?;
+        self.file.sync_data()?;
+        Ok(())
+    }
+
+    /// Reads every complete record from the journal at `path`.
+    ///
+    /// Stops cleanly at a truncated last record, any other malformed record is an error.
+    pub fn read(path: impl AsRef<Path>) -> Result<Vec<JournalRecord>, ManagerError> {
+        let contents = fs::read_to_string(path)?;
+        let lines: Vec<&str> = contents.split('\n').collect();
+        let mut records = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<JournalRecord>(line) {
+                Ok(record) => records.push(record),
+                // the last line has no trailing '\n': the crash happened while writing it
+                Err(_) if i == lines.len() - 1 => break,
+                Err(err) => {
+                    return Err(ManagerError::CorruptedJournal {
+                        line: i + 1,
+                        reason: err.to_string(),
+                    });
+                }
+            }
+        }
+        Ok(records)
+    }
+}
+
+impl<A> Manager<A>
+where
+    A: AllocStrategy,
+{
+    /// Records every following insertion/removal into `journal`.
+    pub fn attach_journal(&mut self, journal: Journal) {
+        self.journal = Some(journal);
+    }
+
+    pub fn detach_journal(&mut self) -> Option<Journal> {
+        self.journal.take()
+    }
+
+    /// Re-applies the events of the journal at `path` that are newer than the current state
+    /// (e.g. a freshly loaded snapshot). Returns the number of applied events.
+    pub fn replay_journal(&mut self, path: impl AsRef<Path>) -> Result<usize, ManagerError> {
+        let mut applied = 0;
+        for (i, record) in Journal::read(path)?.into_iter().enumerate() {
+            if record.seq <= self.journal_seq {
+                continue; // already part of the current state
+            }
+            let corrupted = |err: ManagerError| ManagerError::CorruptedJournal {
+                line: i + 1,
+                reason: err.to_string(),
+            };
+            match record.event {
+                JournalEvent::Insert { slot, item } => {
+                    self._restore_item(slot, item.clone()).map_err(corrupted)?;
+                    self._notify_insert(&slot, &item); // rebuild the intake windows
+                }
+                JournalEvent::Remove { slot, .. } => {
+                    let (head, item) = self
+                        ._remove_item(&slot)
+                        .ok_or(ManagerError::NotFound { slot })
+                        .map_err(corrupted)?;
+                    self._update_maps_on_remove(&head, &item);
+                }
+            }
+            self.journal_seq = record.seq;
+            applied += 1;
+        }
+        Ok(applied)
+    }
+
+    pub(crate) fn _record(&mut self, event: JournalEvent) -> Result<(), ManagerError> {
+        let Some(journal) = self.journal.as_mut() else {
+            return Ok(()); // journaling is disabled
+        };
+        let record = JournalRecord {
+            seq: self.journal_seq + 1,
+            timestamp: self.clock.now(),
+            event,
+        };
+        journal.append(&record)?;
+        self.journal_seq = record.seq;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Journal;
+    use crate::allocators::DefaultAllocator;
+    use crate::{Item, Manager, Quality, WarehouseLayout};
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+
+    #[test]
+    fn test_journal_replay() {
+        let dir = std::env::temp_dir().join(format!("journal-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let journal_path = dir.join("warehouse.journal");
+        let snapshot_path = dir.join("warehouse.json");
+        let _ = fs::remove_file(&journal_path);
+
+        let layout = WarehouseLayout::new(3, 3, 3);
+        let mut manager = Manager::new(layout, DefaultAllocator::default(), Vec::new());
+        manager.attach_journal(Journal::open(&journal_path).unwrap());
+
+        manager.insert_item(Item::new(0, "Flour", 10, Quality::Normal)).unwrap();
+        manager.insert_item(Item::new(1, "Wood", 5, Quality::OverSized { size: 2 })).unwrap();
+        manager.save_snapshot(&snapshot_path).unwrap();
+        manager.insert_item(Item::new(2, "Salt", 1, Quality::Normal)).unwrap();
+        manager.remove_item(0, 0, 2).unwrap(); // covered zone of item 1
+        manager.insert_item(Item::new(3, "Rice", 1, Quality::Normal)).unwrap();
+
+        // simulate a crash while writing the last record
+        let mut file = OpenOptions::new().append(true).open(&journal_path).unwrap();
+        file.write_all(b"{\"seq\":6,\"timest").unwrap();
+        drop(file);
+        assert_eq!(Journal::read(&journal_path).unwrap().len(), 5);
+
+        // replay the whole journal on an empty Manager
+        let mut recovered = Manager::new(layout, DefaultAllocator::default(), Vec::new());
+        assert_eq!(recovered.replay_journal(&journal_path).unwrap(), 5);
+        assert_eq!(recovered.map_ids, manager.map_ids);
+        assert_eq!(recovered.map_occupancy, manager.map_occupancy);
+        assert_eq!(recovered.inventory, manager.inventory);
+        assert_eq!(
+            recovered.get_item(0, 0, 0).unwrap().unwrap().timestamp(),
+            manager.get_item(0, 0, 0).unwrap().unwrap().timestamp()
+        );
+
+        // replay on top of the snapshot only applies the newer events
+        let mut recovered = Manager::<DefaultAllocator>::load_snapshot(&snapshot_path).unwrap();
+        assert_eq!(recovered.replay_journal(&journal_path).unwrap(), 3);
+        assert_eq!(recovered.map_ids, manager.map_ids);
+        assert_eq!(recovered.inventory, manager.inventory);
+
+        // reopening discards the truncated record and keeps numbering after the last event
+        recovered.attach_journal(Journal::open(&journal_path).unwrap());
+        recovered.insert_item(Item::new(4, "Oil", 1, Quality::Normal)).unwrap();
+        let records = Journal::read(&journal_path).unwrap();
+        assert_eq!(records.len(), 6);
+        assert_eq!(records.last().unwrap().seq, 6);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
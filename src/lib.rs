@@ -0,0 +1,1118 @@
+//! Inventory engine for a distribution warehouse.
+//!
+//! The warehouse is made of rows, each row has shelves and each shelf has zones.
+//! Every zone stores at most one [`Item`], except [`Quality::OverSized`] items which span several
+//! contiguous zones of the same shelf.
+//! The [`Manager`] keeps the inventory and its reverse indexes, asks an [`AllocStrategy`] for a
+//! free [`Slot`] and checks every new item against its list of [`Filter`]s.
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::convert::From;
+use std::fmt::{Debug, Display};
+
+pub mod allocators;
+pub mod clock;
+pub mod csv_io;
+pub mod errors;
+pub mod filters;
+pub mod journal;
+pub mod reservation;
+pub mod rules;
+pub mod snapshot;
+pub mod view;
+
+pub use crate::allocators::{AllocExplanation, AllocStrategy, Allocator, DefaultAllocator, SlotCheck, SlotSuggestion};
+#[cfg(feature = "alloc-greedy")]
+pub use crate::allocators::GreedyAllocator;
+#[cfg(feature = "alloc-round-robin")]
+pub use crate::allocators::RoundRobinAllocator;
+#[cfg(feature = "alloc-best-fit")]
+pub use crate::allocators::BestFitAllocator;
+pub use crate::clock::{Clock, FixedClock, SystemClock};
+pub use crate::errors::ManagerError;
+pub use crate::filters::{Filter, FilterVerdict, Severity};
+pub use crate::journal::Journal;
+pub use crate::reservation::Reservation;
+pub use crate::view::InventoryView;
+use crate::journal::JournalEvent;
+
+/// Dimensions of the warehouse: number of rows, shelves per row and zones per shelf.
+// Note: keep every dimension >= 3 for cargo tests to be valid
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+pub struct WarehouseLayout {
+    pub rows: usize,
+    pub shelves: usize,
+    pub zones: usize,
+}
+
+impl WarehouseLayout {
+    pub fn new(rows: usize, shelves: usize, zones: usize) -> Self {
+        Self { rows, shelves, zones }
+    }
+
+    /// Returns `true` if `slot` is inside the warehouse bounds.
+    pub fn contains(&self, slot: &Slot) -> bool {
+        slot.row < self.rows && slot.shelf < self.shelves && slot.zone < self.zones
+    }
+
+    /// Distance from the base of the farthest slot (see [`Slot::distance`]).
+    pub fn max_distance(&self) -> usize {
+        self.rows.saturating_sub(1) + self.shelves.saturating_sub(1) + self.zones.saturating_sub(1)
+    }
+
+    /// Total number of zones in the warehouse.
+    pub fn capacity(&self) -> usize {
+        self.rows * self.shelves * self.zones
+    }
+}
+
+impl Default for WarehouseLayout {
+    fn default() -> Self {
+        Self::new(3, 3, 3)
+    }
+}
+
+impl Display for WarehouseLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} rows x {} shelves x {} zones",
+            self.rows, self.shelves, self.zones
+        )
+    }
+}
+
+/// Address of a single zone: `row`, `shelf` and `zone`, all starting at 0 (closest to the base).
+#[derive(Hash, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+pub struct Slot {
+    row: usize,
+    shelf: usize,
+    zone: usize,
+}
+
+impl Slot {
+    /// Checked constructor, use it for any coordinates coming from outside the [`Manager`].
+    ///
+    /// Returns [`ManagerError::InvalidSlot`] if the coordinates are out of `layout` bounds.
+    pub fn new(row: usize, shelf: usize, zone: usize, layout: &WarehouseLayout) -> Result<Self, ManagerError> {
+        let slot = Self { row, shelf, zone };
+        if !layout.contains(&slot) {
+            return Err(ManagerError::InvalidSlot {
+                slot,
+                layout: *layout,
+            });
+        }
+        Ok(slot)
+    }
+
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    pub fn shelf(&self) -> usize {
+        self.shelf
+    }
+
+    pub fn zone(&self) -> usize {
+        self.zone
+    }
+
+    pub fn as_tuple(&self) -> (usize, usize, usize) {
+        (self.row, self.shelf, self.zone)
+    }
+
+    pub fn as_array(&self) -> [usize; 3] {
+        [self.row, self.shelf, self.zone]
+    }
+
+    /// Manhattan distance from the base.
+    pub fn distance(&self) -> usize {
+        self.as_array().iter().sum()
+    }
+}
+
+impl Display for Slot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}|{}|{}]", self.row, self.shelf, self.zone)
+    } // FIXME: choose a better string representation...
+}
+
+impl Debug for Slot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+// Note: the From impls do not check the warehouse bounds (see Slot::new)
+impl From<(usize, usize, usize)> for Slot {
+    fn from(value: (usize, usize, usize)) -> Self {
+        Self {
+            row: value.0,
+            shelf: value.1,
+            zone: value.2,
+        }
+    }
+}
+
+impl From<[usize; 3]> for Slot {
+    fn from(value: [usize; 3]) -> Self {
+        Self {
+            row: value[0],
+            shelf: value[1],
+            zone: value[2],
+        }
+    }
+}
+
+/// Storage category of an [`Item`].
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Quality {
+    /// Perishable item that must be stored at most at row `max_row`.
+    Fragile {
+        expiration_date: DateTime<Local>,
+        max_row: usize,
+    },
+    /// Item spanning `size` contiguous zones of the same shelf.
+    OverSized {
+        size: usize,
+    },
+    /// Item without extra requirements.
+    Normal,
+}
+
+impl Quality {
+    /// Category of the quality, without its parameters.
+    pub fn kind(&self) -> QualityKind {
+        match self {
+            Quality::Fragile { .. } => QualityKind::Fragile,
+            Quality::OverSized { .. } => QualityKind::OverSized,
+            Quality::Normal => QualityKind::Normal,
+        }
+    }
+}
+
+impl Display for Quality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Quality::Fragile { .. } => write!(f, "Fragile"),
+            Quality::OverSized { .. } => write!(f, "OverSized"),
+            Quality::Normal => write!(f, "Normal"),
+        }
+    }
+}
+
+impl Debug for Quality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Quality::Fragile {
+                expiration_date,
+                max_row: max_dist,
+            } => write!(f, "Fragile ({}, {})", expiration_date, max_dist),
+            Quality::OverSized { size } => write!(f, "OverSized ({})", size),
+            Quality::Normal => write!(f, "Normal"),
+        }
+    }
+}
+
+/// [`Quality`] without its parameters, used to count items per category.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub enum QualityKind {
+    Fragile,
+    OverSized,
+    Normal,
+}
+
+impl Display for QualityKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+/// A pallet: its quantity can not be changed, but the same item can be stored several times.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Item {
+    pub id: usize,
+    pub name: String,
+    pub quantity: usize,
+    pub quality: Quality,
+    // additional fields
+    timestamp: Option<DateTime<Local>>,
+}
+
+impl Item {
+    pub fn new(id: usize, name: &str, quantity: usize, quality: Quality) -> Self {
+        Self {
+            id,
+            name: name.to_string(),
+            quantity,
+            quality,
+            timestamp: None,
+        }
+    }
+
+    fn update_timestamp(&mut self, now: DateTime<Local>) {
+        self.timestamp = Some(now);
+    }
+
+    /// Time at which the item was inserted into the warehouse (`None` until it is stored).
+    pub fn timestamp(&self) -> Option<DateTime<Local>> {
+        self.timestamp
+    }
+
+    /// Number of contiguous zones required to store the item.
+    pub fn size(&self) -> usize {
+        match self.quality {
+            Quality::Normal | Quality::Fragile { .. } => 1,
+            Quality::OverSized { size } => size,
+        }
+    }
+
+    /// Every zone occupied by the item when its first zone is at `slot`.
+    pub fn covered_slots(&self, slot: &Slot) -> impl Iterator<Item = Slot> + use<> {
+        let (row, shelf, zone) = slot.as_tuple();
+        (zone..zone.saturating_add(self.size())).map(move |z| Slot::from((row, shelf, z)))
+    }
+}
+
+impl Display for Item {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let timestamp = self
+            .timestamp
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S %:z").to_string())
+            .unwrap_or_else(|| "???".to_string());
+
+        match self.quality {
+            Quality::Fragile {
+                expiration_date,
+                max_row,
+            } => {
+                write!(
+                    f,
+                    "[Item {}: {}] [Qty: {}, {}] [Created at: {}] [Expires at: {}] [Must be stored at most at row {}]",
+                    self.id,
+                    self.name,
+                    self.quantity,
+                    self.quality,
+                    timestamp,
+                    expiration_date.format("%Y-%m-%d %H:%M:%S %:z"),
+                    max_row
+                )
+            }
+            Quality::OverSized { size } => {
+                write!(
+                    f,
+                    "[Item {}: {}] [Qty: {}, {}] [Created at: {}] [Requires {} contiguous zones]",
+                    self.id, self.name, self.quantity, self.quality, timestamp, size
+                )
+            }
+            Quality::Normal => {
+                write!(
+                    f,
+                    "[Item {}: {}] [Qty: {}, {}] [Created at: {}]",
+                    self.id, self.name, self.quantity, self.quality, timestamp
+                )
+            }
+        }
+    }
+}
+
+impl Debug for Item {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl PartialEq for Item {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.name == other.name
+            && self.quantity == other.quantity
+            && self.quality == other.quality
+    }
+}
+
+/// Outcome of a successful [`Manager::insert_item`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Insertion {
+    pub slot: Slot,
+    pub warnings: Vec<FilterVerdict>, // triggered filters with Severity::Warn
+}
+
+/// [`Manager`] using the strategy selected through cargo features.
+pub type DefaultManager = Manager<DefaultAllocator>;
+
+/// Inventory manager, generic over the allocation strategy.
+///
+/// Keeps reverse indexes so that lookups by id, name, zone and expiration date do not
+/// need to scan the whole inventory.
+#[derive(Debug)]
+pub struct Manager<A>
+where
+    A: AllocStrategy,
+{
+    layout: WarehouseLayout,
+    inventory: HashMap<Slot, Item>,
+    allocator: A,
+    filters: Vec<Box<dyn Filter>>, // need dynamic dispatch to hold different impls of Filter
+    disabled_filters: BTreeSet<String>, // names of the filters that are not evaluated
+
+    // reverse-maps
+    map_ids: HashMap<usize, usize>,       // id, count
+    map_units: HashMap<usize, usize>,     // id, total quantity
+    map_names: HashMap<String, usize>,    // name, count
+    map_name_units: HashMap<String, usize>, // name, total quantity
+    map_name_ids: HashMap<String, HashMap<usize, usize>>, // name, (id, count)
+    map_slots: HashMap<usize, Vec<Slot>>, // id, list of slots
+    map_occupancy: HashMap<Slot, Slot>,   // covered zone, first zone of the owning item
+    map_qualities: HashMap<QualityKind, usize>, // quality kind, count
+    // only used for Quality::Fragile items
+    map_dates: BTreeMap<DateTime<Local>, Vec<Slot>>, // date, list of ids
+
+    reservations: HashMap<Slot, Reservation>, // zones are held in map_occupancy (see reservation.rs)
+
+    clock: Box<dyn Clock>, // time of insertions and filter checks
+
+    // append-only log of insertions/removals (see journal.rs)
+    journal: Option<Journal>,
+    journal_seq: u64, // sequence number of the last event written or replayed
+}
+
+impl<A> Manager<A>
+where
+    A: AllocStrategy,
+{
+    pub fn new(layout: WarehouseLayout, allocator: A, filters: Vec<Box<dyn Filter>>) -> Manager<A> {
+        Manager {
+            layout,
+            inventory: HashMap::new(),
+            allocator,
+            filters,
+            disabled_filters: BTreeSet::new(),
+
+            map_ids: HashMap::new(),
+            map_units: HashMap::new(),
+            map_names: HashMap::new(),
+            map_name_units: HashMap::new(),
+            map_name_ids: HashMap::new(),
+            map_slots: HashMap::new(),
+            map_occupancy: HashMap::new(),
+            map_qualities: HashMap::new(),
+            map_dates: BTreeMap::new(),
+
+            reservations: HashMap::new(),
+
+            clock: Box::new(SystemClock),
+
+            journal: None,
+            journal_seq: 0,
+        }
+    }
+
+    pub fn allocator(&self) -> &A {
+        &self.allocator
+    }
+
+    /// Switches the allocation strategy, keeping the inventory. Returns the previous one.
+    pub fn set_allocator(&mut self, allocator: A) -> A {
+        std::mem::replace(&mut self.allocator, allocator)
+    }
+
+    /// Replaces the source of the current time (wall-clock time by default).
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Replaces the list of filters.
+    pub fn set_filters(&mut self, filters: Vec<Box<dyn Filter>>) {
+        self.filters = filters;
+        self.disabled_filters.clear();
+    }
+
+    /// Appends a filter to the list of filters.
+    pub fn insert_filter(&mut self, filter: Box<dyn Filter>) {
+        self.filters.push(filter);
+    }
+
+    /// Every filter with whether it is enabled. Filters are identified by their name (Display).
+    pub fn filters(&self) -> impl Iterator<Item = (&dyn Filter, bool)> {
+        self.filters
+            .iter()
+            .map(|f| (f.as_ref(), !self.disabled_filters.contains(&f.to_string())))
+    }
+
+    /// Removes the first filter named `name`.
+    pub fn remove_filter(&mut self, name: &str) -> Result<Box<dyn Filter>, ManagerError> {
+        let index = self._find_filter(name)?;
+        let filter = self.filters.remove(index);
+        if self._find_filter(name).is_err() {
+            self.disabled_filters.remove(name); // forget the state of the last filter with this name
+        }
+        Ok(filter)
+    }
+
+    /// Stops evaluating the filters named `name`, they keep being notified of insertions.
+    pub fn disable_filter(&mut self, name: &str) -> Result<(), ManagerError> {
+        self._find_filter(name)?;
+        self.disabled_filters.insert(name.to_string());
+        Ok(())
+    }
+
+    /// Evaluates again the filters named `name`.
+    pub fn enable_filter(&mut self, name: &str) -> Result<(), ManagerError> {
+        self._find_filter(name)?;
+        self.disabled_filters.remove(name);
+        Ok(())
+    }
+
+    fn _find_filter(&self, name: &str) -> Result<usize, ManagerError> {
+        self.filters
+            .iter()
+            .position(|f| f.to_string() == name)
+            .ok_or_else(|| ManagerError::UnknownFilter {
+                name: name.to_string(),
+            })
+    }
+
+    /// Clears the state of stateful filters (e.g. the intake windows of
+    /// [`IntakeQuota`](crate::filters::IntakeQuota)).
+    pub fn reset_filters(&mut self) {
+        for filter in self.filters.iter_mut() {
+            filter.reset();
+        }
+    }
+
+    /// Evaluates every filter on `item`.
+    ///
+    /// Returns the verdicts of the triggered [`Severity::Warn`] filters if the item is allowed,
+    /// or the verdicts of the triggered [`Severity::Reject`] filters otherwise.
+    pub fn is_allowed_by_filters(&self, item: &Item) -> Result<Vec<FilterVerdict>, Vec<FilterVerdict>> {
+        self._evaluate_filters(item, self)
+    }
+
+    pub(crate) fn _evaluate_filters(
+        &self,
+        item: &Item,
+        inventory: &dyn InventoryView,
+    ) -> Result<Vec<FilterVerdict>, Vec<FilterVerdict>> {
+        // no short-circuit: the operator should see every reason at once
+        let (rejections, warnings): (Vec<&dyn Filter>, Vec<&dyn Filter>) = self
+            .filters
+            .iter()
+            .map(|f| f.as_ref())
+            .filter(|f| !self.disabled_filters.contains(&f.to_string()))
+            .filter(|f| !f.filter(item, inventory))
+            .partition(|f| f.severity() == Severity::Reject);
+        let verdicts = |filters: Vec<&dyn Filter>| {
+            filters
+                .into_iter()
+                .map(|f| FilterVerdict::new(f, item, inventory))
+                .collect()
+        };
+        if rejections.is_empty() {
+            Ok(verdicts(warnings))
+        } else {
+            Err(verdicts(rejections))
+        }
+    }
+
+    fn _check_filters(&self, item: &Item) -> Result<Vec<FilterVerdict>, ManagerError> {
+        self.is_allowed_by_filters(item)
+            .map_err(|filters| ManagerError::FilteredItem {
+                item: item.clone(),
+                filters,
+            })
+    }
+
+    /// Checks `item` against the filters, allocates a slot for it and stores it.
+    ///
+    /// Returns the slot where the item was stored, with the warnings of the soft filters.
+    pub fn insert_item(&mut self, mut item: Item) -> Result<Insertion, ManagerError> {
+        self.expire_reservations();
+        self._check_size(&item)?;
+        let warnings = self._check_filters(&item)?; // short-circuit if some filter rejects it
+        let slot = self._alloc(&item)?;
+
+        item.update_timestamp(self.clock.now());
+        self._insert_item(slot, item)?;
+        Ok(Insertion { slot, warnings })
+    }
+
+    fn _alloc(&mut self, item: &Item) -> Result<Slot, ManagerError> {
+        // the strategy picks the slot, the dry run only describes a failure
+        // (taken first, alloc may move the strategy state, e.g. RoundRobin)
+        let allocator = self.allocator.to_string();
+        let explanation = self.explain_allocation(item);
+        self.allocator
+            .alloc(item, &self.map_occupancy, &self.layout)
+            .ok_or_else(|| ManagerError::FailedAllocation {
+                allocator,
+                diagnostics: Box::new(explanation.diagnostics),
+                item: item.clone(),
+            })
+    }
+
+    /// Slot where `item` would be allocated, with a breakdown of the rejected candidates.
+    ///
+    /// Nothing is changed (not even the allocator state) and filters are not checked.
+    pub fn explain_allocation(&self, item: &Item) -> AllocExplanation {
+        self.allocator.explain(item, &self.map_occupancy, &self.layout)
+    }
+
+    /// Up to `k` valid slots for `item`, best first (see [`AllocStrategy::suggest`]).
+    ///
+    /// Nothing is changed and filters are not checked.
+    pub fn suggest_slots(&self, item: &Item, k: usize) -> Vec<SlotSuggestion> {
+        self.allocator.suggest(item, &self.map_occupancy, &self.layout, k)
+    }
+
+    /// Checks `item` against the filters and stores it at `slot`, bypassing the allocator
+    /// (e.g. a slot picked by the operator among [`suggest_slots`](Manager::suggest_slots)).
+    ///
+    /// The slot must satisfy the same rules as an allocated one: [`ManagerError::InvalidSlot`]
+    /// outside the layout, [`ManagerError::SlotOccupied`] if the zone is in use,
+    /// [`ManagerError::SlotReserved`] if it is held by a [`Reservation`],
+    /// [`ManagerError::SlotBlocked`] if one of the zones an OverSized item needs is in use,
+    /// [`ManagerError::SlotUnavailable`] if it goes past the end of the shelf and
+    /// [`ManagerError::FragileRowExceeded`] above the `max_row` of a Fragile item.
+    /// OverSized items of size 0 or longer than a shelf are rejected with [`ManagerError::InvalidItemSize`].
+    pub fn place_item_at(&mut self, slot: Slot, mut item: Item) -> Result<Insertion, ManagerError> {
+        item.update_timestamp(self.clock.now());
+        self._place_item(slot, item)
+    }
+
+    pub(crate) fn _place_item(&mut self, slot: Slot, item: Item) -> Result<Insertion, ManagerError> {
+        self.expire_reservations();
+        let warnings = self._check_filters(&item)?;
+        self._check_placement(&slot, &item)?;
+        self._insert_item(slot, item)?;
+        Ok(Insertion { slot, warnings })
+    }
+
+    fn _insert_item(&mut self, slot: Slot, item: Item) -> Result<(), ManagerError> {
+        // never overwrite a stored item, the reverse-maps would count both
+        if self.inventory.contains_key(&slot) {
+            return Err(ManagerError::SlotOccupied { slot, head: slot });
+        }
+        self._record(JournalEvent::Insert {
+            slot,
+            item: item.clone(),
+        })?; // nothing is stored if the event could not be persisted
+        self._update_maps_on_insert(&slot, &item);
+        self._notify_insert(&slot, &item);
+        self.inventory.insert(slot, item);
+        Ok(())
+    }
+
+    pub(crate) fn _notify_insert(&mut self, slot: &Slot, item: &Item) {
+        // let stateful filters (e.g. intake quotas) account for the new item
+        let now = item.timestamp.unwrap_or_else(|| self.clock.now());
+        for filter in self.filters.iter_mut() {
+            filter.on_insert(slot, item, now);
+        }
+    }
+
+    fn _check_size(&self, item: &Item) -> Result<(), ManagerError> {
+        // a pallet covering no zone could not be found again, one longer than a shelf fits nowhere
+        if item.size() == 0 || item.size() > self.layout.zones {
+            return Err(ManagerError::InvalidItemSize {
+                item: item.clone(),
+                zones: self.layout.zones,
+            });
+        }
+        Ok(())
+    }
+
+    fn _check_placement(&self, slot: &Slot, item: &Item) -> Result<(), ManagerError> {
+        self._check_placement_in(slot, item, &self.map_occupancy)
+    }
+
+    pub(crate) fn _check_placement_in(
+        &self,
+        slot: &Slot,
+        item: &Item,
+        occupancy: &HashMap<Slot, Slot>,
+    ) -> Result<(), ManagerError> {
+        // same rules as the allocators, with a typed error for each rejection
+        Slot::new(slot.row, slot.shelf, slot.zone, &self.layout)?;
+        self._check_size(item)?;
+        match self.allocator.check_slot(slot, item, occupancy, &self.layout) {
+            SlotCheck::Available => Ok(()),
+            SlotCheck::OutOfBounds => Err(ManagerError::SlotUnavailable {
+                slot: *slot,
+                item: item.clone(),
+            }),
+            SlotCheck::BackwardBlocked | SlotCheck::ForwardBlocked => {
+                if let Some(&head) = occupancy.get(slot) {
+                    if self.reservations.contains_key(&head) {
+                        return Err(ManagerError::SlotReserved { slot: *slot, head });
+                    }
+                    return Err(ManagerError::SlotOccupied { slot: *slot, head });
+                }
+                let blocked_at = item
+                    .covered_slots(slot)
+                    .find(|covered| occupancy.contains_key(covered))
+                    .unwrap_or(*slot);
+                Err(ManagerError::SlotBlocked {
+                    slot: *slot,
+                    blocked_at,
+                })
+            }
+            SlotCheck::FragileRow => Err(ManagerError::FragileRowExceeded {
+                slot: *slot,
+                max_row: match item.quality {
+                    Quality::Fragile { max_row, .. } => max_row,
+                    _ => unreachable!("only Fragile items have a max. row"),
+                },
+            }),
+        }
+    }
+
+    fn _restore_item(&mut self, slot: Slot, item: Item) -> Result<(), ManagerError> {
+        // stores an item at a known slot, keeping its original timestamp
+        // (used to rebuild the Manager state from persisted data)
+        self._check_placement(&slot, &item)?;
+        self._update_maps_on_insert(&slot, &item);
+        self.inventory.insert(slot, item);
+        Ok(())
+    }
+
+    fn _update_maps_on_insert(&mut self, slot: &Slot, item: &Item) {
+        *self.map_ids.entry(item.id).or_insert(0) += 1;
+        *self.map_units.entry(item.id).or_insert(0) += item.quantity;
+        *self.map_names.entry(item.name.clone()).or_insert(0) += 1;
+        *self.map_name_units.entry(item.name.clone()).or_insert(0) += item.quantity;
+        *self
+            .map_name_ids
+            .entry(item.name.clone())
+            .or_default()
+            .entry(item.id)
+            .or_insert(0) += 1;
+        *self.map_qualities.entry(item.quality.kind()).or_insert(0) += 1;
+        self.map_slots.entry(item.id).or_insert(vec![]).push(*slot);
+        for covered in item.covered_slots(slot) {
+            self.map_occupancy.insert(covered, *slot);
+        }
+
+        if let Quality::Fragile {
+            expiration_date, ..
+        } = item.quality
+        {
+            self.map_dates
+                .entry(expiration_date)
+                .or_insert(vec![])
+                .push(*slot);
+        }
+    }
+
+    pub fn layout(&self) -> &WarehouseLayout {
+        &self.layout
+    }
+
+    /// Returns the item stored at the given zone, any zone covered by an OverSized item works.
+    pub fn get_item(&self, row: usize, shelf: usize, zone: usize) -> Result<Option<&Item>, ManagerError> {
+        let slot = Slot::new(row, shelf, zone, &self.layout)?;
+        Ok(self._get_item(&slot))
+    }
+
+    fn _get_item(&self, slot: &Slot) -> Option<&Item> {
+        // resolve zones covered by OverSized items to the slot where the item is stored
+        let head = self.map_occupancy.get(slot)?;
+        self.inventory.get(head)
+    }
+
+    /// Removes the item stored at the given zone, any zone covered by an OverSized item works.
+    ///
+    /// Returns [`ManagerError::NotFound`] if the zone is empty.
+    pub fn remove_item(&mut self, row: usize, shelf: usize, zone: usize) -> Result<Item, ManagerError> {
+        let slot = Slot::new(row, shelf, zone, &self.layout)?;
+        let head = *self
+            .map_occupancy
+            .get(&slot)
+            .filter(|head| self.inventory.contains_key(head)) // reserved zones hold no item yet
+            .ok_or(ManagerError::NotFound { slot })?;
+        self._record(JournalEvent::Remove {
+            slot: head,
+            item: self.inventory[&head].clone(),
+        })?; // nothing is removed if the event could not be persisted
+        let (head, item) = self
+            ._remove_item(&slot)
+            .ok_or(ManagerError::NotFound { slot })?;
+        self._update_maps_on_remove(&head, &item);
+        Ok(item) // keeps the timestamp of when it was inserted
+    }
+
+    fn _remove_item(&mut self, slot: &Slot) -> Option<(Slot, Item)> {
+        // any covered zone can be used to remove an OverSized item
+        let head = *self.map_occupancy.get(slot)?;
+        self.inventory.remove(&head).map(|item| (head, item))
+    }
+
+    fn _update_maps_on_remove(&mut self, slot: &Slot, item: &Item) {
+        self.map_ids.entry(item.id).and_modify(|count| *count -= 1);
+        self.map_units
+            .entry(item.id)
+            .and_modify(|units| *units -= item.quantity);
+        self.map_names
+            .entry(item.name.clone())
+            .and_modify(|count| *count -= 1);
+        self.map_name_units
+            .entry(item.name.clone())
+            .and_modify(|units| *units -= item.quantity);
+        self.map_name_ids.entry(item.name.clone()).and_modify(|ids| {
+            ids.entry(item.id).and_modify(|count| *count -= 1);
+            ids.retain(|_, count| *count != 0);
+        });
+        self.map_qualities
+            .entry(item.quality.kind())
+            .and_modify(|count| *count -= 1);
+        self.map_slots
+            .entry(item.id)
+            .and_modify(|vec| vec.retain(|s| *s != *slot));
+        for covered in item.covered_slots(slot) {
+            self.map_occupancy.remove(&covered);
+        }
+
+        if let Quality::Fragile {
+            expiration_date, ..
+        } = item.quality
+        {
+            self.map_dates
+                .entry(expiration_date)
+                .and_modify(|vec| vec.retain(|s| *s != *slot));
+        }
+
+        // clean-up empty entries
+        // FIXME: inefficient, because iterates over HashMap when at most a single entry needs to
+        //        be cleaned up. Instead, it would be better to clean-up using Entry API just after
+        //        we are done updating the HashMaps
+        self.map_ids.retain(|_, count| *count != 0);
+        self.map_units.retain(|id, _| self.map_ids.contains_key(id));
+        self.map_names.retain(|_, count| *count != 0);
+        self.map_name_units.retain(|name, _| self.map_names.contains_key(name));
+        self.map_name_ids.retain(|_, ids| !ids.is_empty());
+        self.map_slots.retain(|_, vec| !vec.is_empty());
+        self.map_dates.retain(|_, vec| !vec.is_empty());
+        self.map_qualities.retain(|_, count| *count != 0);
+    }
+
+    /// Every stored item, ordered by ascending name.
+    pub fn ord_by_name(&self) -> Vec<&Item> {
+        // convert to Vec for O(N log(N)) sorting
+        let mut items: Vec<&Item> = self.inventory.values().collect();
+        items.sort_by(|a, b| a.name.cmp(&b.name));
+        items // sort refs to avoid copying (low memory footprint)
+    }
+
+    /// Number of stored items with the given id.
+    pub fn count_id(&self, id: usize) -> usize {
+        // TODO: should also return a bool to indicate count > 0?
+        // TODO: should return an Option or Result to indicate count = 0?
+        self.map_ids.get(&id).map_or(0, |v| *v)
+    }
+
+    /// Number of stored items with the given name.
+    pub fn count_name(&self, name: &str) -> usize {
+        // TODO: should also return a bool to indicate count > 0?
+        // TODO: should return an Option or Result to indicate count = 0?
+        self.map_names.get(name).map_or(0, |v| *v)
+    }
+
+    /// Slots where items with the given id are stored.
+    pub fn find_id(&self, id: usize) -> Option<&Vec<Slot>> {
+        // TODO: should also return a bool to indicate count > 0?
+        // TODO: should return an Option or Result to indicate count = 0?
+        self.map_slots.get(&id)
+    }
+
+    /// Fragile items that expire at or before `date`.
+    pub fn find_expired(&self, date: DateTime<Local>) -> Vec<Item> {
+        self.map_dates
+            .range(..=date)
+            .flat_map(|(_, ids)| ids)
+            .filter_map(|slot| self._get_item(slot))
+            .cloned()
+            .collect::<Vec<_>>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AllocStrategy, Item, Manager, Quality, Slot, SlotCheck, WarehouseLayout};
+    use crate::allocators::DefaultAllocator;
+    use crate::errors::ManagerError;
+    use crate::filters::{AllowIds, Filter, LimitOverSized};
+    use chrono::{Local, NaiveDateTime, TimeZone};
+    use itertools::Itertools;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_manager() {
+        // FIXME: tbh this is not a good unit test because it relies on RoundRobin correctness
+        //        e.g. checking expected Slots after assigning items
+        //        Proper testing would require manually setting up the items in the desired slots
+        let exp_date =
+            NaiveDateTime::parse_from_str("2020-01-01 14:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let exp_date = Local.from_local_datetime(&exp_date).unwrap(); // DateTime<Local>
+
+        // no filters, RoundRobin
+        let layout = WarehouseLayout::new(3, 3, 3);
+        let mut manager = Manager::new(layout, DefaultAllocator::default(), Vec::new());
+
+        let item0 = Item::new(0, "Flour", 10, Quality::Normal);
+        let item1 = Item::new(1, "Wood", 5, Quality::OverSized { size: 2 });
+        let item2 = Item::new(2, "Glass", 2, Quality::Fragile { expiration_date: exp_date, max_row: 1 });
+
+        manager.insert_item(item0.clone()).unwrap();  // Normal
+        manager.insert_item(item0.clone()).unwrap();  // Normal
+        manager.insert_item(item0.clone()).unwrap();  // Normal
+        manager.insert_item(item0.clone()).unwrap();  // Normal
+        manager.insert_item(item1.clone()).unwrap();  // OverSized
+        manager.insert_item(item0.clone()).unwrap();  // Normal
+        manager.insert_item(item2.clone()).unwrap();  // Fragile
+        manager.insert_item(item2.clone()).unwrap();  // Fragile
+        manager.insert_item(item0.clone()).unwrap();  // Normal
+        manager.insert_item(item2.clone()).unwrap();  // Fragile
+
+        {
+            let item0 = Item::new(0, "Flour", 10, Quality::Normal);
+            let item1 = Item::new(1, "Wood", 5, Quality::OverSized { size: 2 });
+            let item2 = Item::new(2, "Glass", 2, Quality::Fragile { expiration_date: exp_date, max_row: 1 });
+            let ordered = manager.ord_by_name();
+            assert_eq!(ordered.len(), 10);
+            assert!(&ordered[0..6].iter().all_equal());
+            assert!(&ordered[0..6].iter().all(|x| **x == item0));
+            assert!(&ordered[6..9].iter().all_equal());
+            assert!(&ordered[6..9].iter().all(|x| **x == item2));
+            assert!(&ordered[9..9].iter().all_equal());
+            assert!(&ordered[9..9].iter().all(|x| **x == item1));
+        }
+
+        assert_eq!(manager.count_id(0), 6);
+        assert_eq!(manager.count_id(1), 1);
+        assert_eq!(manager.count_id(2), 3);
+
+        assert_eq!(manager.count_name("Flour"), 6);
+        assert_eq!(manager.count_name("Wood"), 1);
+        assert_eq!(manager.count_name("Glass"), 3);
+
+        let slot = manager.find_id(1).unwrap();
+        assert_eq!(slot.len(), 1);
+        #[cfg(feature = "alloc-round-robin")]
+        assert_eq!(slot[0], Slot::from((0, 1, 1)));
+
+        let expired = manager.find_expired(Local::now());
+        assert_eq!(expired.len(), 3);
+        assert!(expired.iter().all(|item| item == &item2));
+    }
+
+    #[test]
+    fn test_oversized_occupancy() {
+        let layout = WarehouseLayout::new(3, 3, 3);
+        let mut manager = Manager::new(layout, DefaultAllocator::default(), Vec::new());
+
+        let item = Item::new(0, "Wood", 5, Quality::OverSized { size: 3 });
+        manager.insert_item(item.clone()).unwrap();
+
+        // every covered zone resolves to the same pallet
+        for zone in 0..3 {
+            assert_eq!(manager.get_item(0, 0, zone).unwrap(), Some(&item));
+        }
+        assert_eq!(manager.find_id(0).unwrap(), &vec![Slot::from((0, 0, 0))]);
+
+        // removing from a covered zone frees the whole pallet
+        let removed = manager.remove_item(0, 0, 2).unwrap();
+        assert_eq!(removed, item);
+        assert!(removed.timestamp.is_some());
+        assert_eq!(manager.count_id(0), 0);
+        assert!(manager.find_id(0).is_none());
+        assert!((0..3).all(|zone| manager.get_item(0, 0, zone).unwrap().is_none()));
+        assert!(manager.map_occupancy.is_empty());
+
+        // a pallet covering no zone could not be found again
+        let empty = Item::new(1, "Pallet", 1, Quality::OverSized { size: 0 });
+        assert!(manager.insert_item(empty).is_err_and(|err| matches!(
+            err,
+            ManagerError::InvalidItemSize { .. }
+        )));
+        assert_eq!(manager.count_id(1), 0);
+        assert!(manager.find_id(1).is_none());
+
+        // nor can one longer than a shelf, however large its size
+        for size in [4, usize::MAX] {
+            let long = Item::new(2, "Beam", 1, Quality::OverSized { size });
+            assert!(manager.insert_item(long.clone()).is_err_and(|err| matches!(
+                err,
+                ManagerError::InvalidItemSize { zones: 3, .. }
+            )));
+            assert!(manager.place_item_at(Slot::from((0, 0, 0)), long.clone()).is_err_and(|err| matches!(
+                err,
+                ManagerError::InvalidItemSize { .. }
+            )));
+            let slot = Slot::from((0, 0, 1));
+            assert!(!manager.allocator.is_slot_available(&slot, &long, &manager.map_occupancy, &layout));
+            assert_eq!(
+                manager.allocator.check_slot(&slot, &long, &manager.map_occupancy, &layout),
+                SlotCheck::OutOfBounds
+            );
+        }
+        assert!(manager.map_occupancy.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_slot() {
+        let layout = WarehouseLayout::new(2, 3, 4);
+        assert!(Slot::new(1, 2, 3, &layout).is_ok());
+        assert!(Slot::new(2, 0, 0, &layout).is_err());
+        assert!(Slot::new(0, 3, 0, &layout).is_err());
+        assert!(Slot::new(0, 0, 4, &layout).is_err());
+
+        let mut manager = Manager::new(layout, DefaultAllocator::default(), Vec::new());
+        assert!(manager.get_item(0, 0, 0).unwrap().is_none());
+        assert!(manager.get_item(99, 99, 99).is_err_and(|err| matches!(
+            err,
+            ManagerError::InvalidSlot { .. }
+        )));
+        assert!(manager.remove_item(0, 0, 4).is_err_and(|err| matches!(
+            err,
+            ManagerError::InvalidSlot { .. }
+        )));
+        assert!(manager.remove_item(0, 0, 3).is_err_and(|err| matches!(
+            err,
+            ManagerError::NotFound { slot } if slot == Slot::from((0, 0, 3))
+        )));
+    }
+
+    #[test]
+    fn test_place_item_at() {
+        let layout = WarehouseLayout::new(3, 3, 3);
+        let filters: Vec<Box<dyn Filter>> = vec![Box::from(AllowIds::new([0, 1, 2]))];
+        let mut manager = Manager::new(layout, DefaultAllocator::default(), filters);
+
+        let wood = Item::new(0, "Wood", 5, Quality::OverSized { size: 2 });
+        let insertion = manager.place_item_at(Slot::from((1, 2, 1)), wood.clone()).unwrap();
+        assert_eq!(insertion.slot, Slot::from((1, 2, 1)));
+        assert_eq!(manager.get_item(1, 2, 2).unwrap().unwrap().name, "Wood");
+        assert!(manager.get_item(1, 2, 1).unwrap().unwrap().timestamp.is_some());
+        assert_eq!(manager.find_id(0).unwrap(), &vec![Slot::from((1, 2, 1))]);
+        assert_eq!(manager.map_units[&0], 5);
+        #[cfg(feature = "alloc-round-robin")]
+        assert_eq!(manager.allocator.get_prev_alloc(), &None); // the allocator is not involved
+
+        let flour = Item::new(1, "Flour", 1, Quality::Normal);
+        assert!(manager.place_item_at(Slot::from((3, 0, 0)), flour.clone()).is_err_and(|err| matches!(
+            err,
+            ManagerError::InvalidSlot { .. }
+        )));
+        assert!(manager.place_item_at(Slot::from((1, 2, 2)), flour.clone()).is_err_and(|err| matches!(
+            err,
+            ManagerError::SlotOccupied { head, .. } if head == Slot::from((1, 2, 1))
+        )));
+        assert!(manager.place_item_at(Slot::from((1, 2, 0)), wood.clone()).is_err_and(|err| matches!(
+            err,
+            ManagerError::SlotBlocked { blocked_at, .. } if blocked_at == Slot::from((1, 2, 1))
+        )));
+        assert!(manager.place_item_at(Slot::from((0, 0, 2)), wood.clone()).is_err_and(|err| matches!(
+            err,
+            ManagerError::SlotUnavailable { .. }
+        )));
+        let expiration_date = Local::now();
+        let glass = Item::new(2, "Glass", 1, Quality::Fragile { expiration_date, max_row: 1 });
+        assert!(manager.place_item_at(Slot::from((2, 0, 0)), glass.clone()).is_err_and(|err| matches!(
+            err,
+            ManagerError::FragileRowExceeded { max_row: 1, .. }
+        )));
+        assert!(manager.place_item_at(Slot::from((0, 0, 0)), Item::new(3, "Nails", 1, Quality::Normal)).is_err_and(
+            |err| matches!(err, ManagerError::FilteredItem { .. })
+        ));
+        let empty = Item::new(1, "Pallet", 1, Quality::OverSized { size: 0 });
+        assert!(manager.place_item_at(Slot::from((1, 2, 1)), empty.clone()).is_err_and(|err| matches!(
+            err,
+            ManagerError::InvalidItemSize { .. }
+        )));
+        assert!(manager.place_item_at(Slot::from((0, 0, 0)), empty).is_err());
+        // stored items are never overwritten, even if the placement checks were skipped
+        assert!(manager._insert_item(Slot::from((1, 2, 1)), flour.clone()).is_err_and(|err| matches!(
+            err,
+            ManagerError::SlotOccupied { .. }
+        )));
+        assert_eq!(manager.inventory.len(), 1); // nothing stored by the failed placements
+        assert_eq!(manager.count_id(1), 0);
+
+        manager.place_item_at(Slot::from((1, 0, 0)), glass).unwrap();
+        assert_eq!(manager.find_expired(expiration_date).len(), 1);
+    }
+
+    #[test]
+    fn test_custom_alloc() {
+        // a strategy that only implements alloc: the furthest free zone from the base
+        #[derive(Debug)]
+        struct LastFree;
+
+        impl std::fmt::Display for LastFree {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "LastFree")
+            }
+        }
+
+        impl AllocStrategy for LastFree {
+            fn alloc(&mut self, _item: &Item, occupancy: &HashMap<Slot, Slot>, layout: &WarehouseLayout) -> Option<Slot> {
+                let mut candidates = self.candidates(layout).collect::<Vec<Slot>>();
+                candidates.retain(|slot| !occupancy.contains_key(slot));
+                candidates.pop()
+            }
+        }
+
+        let mut manager = Manager::new(WarehouseLayout::new(1, 1, 2), LastFree, Vec::new());
+        let flour = Item::new(0, "Flour", 10, Quality::Normal);
+        assert_eq!(manager.insert_item(flour.clone()).unwrap().slot, Slot::from((0, 0, 1)));
+        assert_eq!(manager.insert_item(flour.clone()).unwrap().slot, Slot::from((0, 0, 0)));
+        assert!(manager.insert_item(flour).is_err_and(|err| matches!(
+            err,
+            ManagerError::FailedAllocation { diagnostics, .. } if diagnostics.examined == 2
+        )));
+    }
+
+    #[test]
+    fn test_manage_filters() {
+        let filters: Vec<Box<dyn Filter>> = vec![
+            Box::from(AllowIds::new([0, 1])),
+            Box::from(LimitOverSized::new(0)),
+            Box::from(LimitOverSized::new(0)),
+        ];
+        let mut manager = Manager::new(WarehouseLayout::new(3, 3, 3), DefaultAllocator::default(), filters);
+        let oversized = Item::new(0, "A", 1, Quality::OverSized { size: 1 });
+        assert!(manager.insert_item(Item::new(2, "B", 1, Quality::Normal)).is_err());
+
+        // disabled filters are skipped
+        manager.disable_filter("AllowIds({0, 1})").unwrap();
+        assert!(manager.insert_item(Item::new(2, "B", 1, Quality::Normal)).is_ok());
+        let states: Vec<(String, bool)> = manager.filters().map(|(f, on)| (f.to_string(), on)).collect();
+        assert_eq!(states[0], ("AllowIds({0, 1})".to_string(), false));
+        assert!(states[1].1 && states[2].1);
+
+        // the state survives a snapshot
+        let mut loaded = Manager::<DefaultAllocator>::from_json(&manager.to_json().unwrap()).unwrap();
+        assert!(loaded.insert_item(Item::new(3, "C", 1, Quality::Normal)).is_ok());
+        loaded.enable_filter("AllowIds({0, 1})").unwrap();
+        assert!(loaded.insert_item(Item::new(3, "C", 1, Quality::Normal)).is_err());
+
+        // filters with the same name are disabled together, but removed one at a time
+        manager.disable_filter("LimitOverSized(0)").unwrap();
+        assert!(manager.insert_item(oversized.clone()).is_ok());
+        manager.remove_filter("LimitOverSized(0)").unwrap();
+        assert!(manager.insert_item(oversized.clone()).is_ok());
+        manager.enable_filter("LimitOverSized(0)").unwrap();
+        assert!(manager.insert_item(oversized.clone()).is_err());
+        manager.remove_filter("LimitOverSized(0)").unwrap();
+        assert!(manager.insert_item(oversized).is_ok());
+
+        assert!(manager.remove_filter("LimitOverSized(0)").is_err_and(|err| matches!(
+            err,
+            ManagerError::UnknownFilter { name } if name == "LimitOverSized(0)"
+        )));
+        assert!(manager.disable_filter("Nope").is_err());
+        assert_eq!(manager.filters().count(), 1);
+    }
+}
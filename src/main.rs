@@ -1,473 +1,480 @@
-use allocators::{AllocStrategy, RoundRobinAllocator};
-use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
-use dialoguer::{theme::ColorfulTheme, Input, MultiSelect, Select, Confirm};
+use chrono::{Local, NaiveDateTime, TimeDelta, TimeZone};
 use console::style;
-use itertools::Itertools;
-use std::collections::{BTreeMap, HashMap, HashSet};
-use std::convert::From;
-use std::fmt::{Debug, Display};
-
-mod allocators;
-mod errors;
-mod filters;
-
-use crate::errors::ManagerError;
-use filters::{BanQuality, Filter, LimitItemQuantity, LimitOverSized};
-use crate::allocators::GreedyAllocator;
-
-// Note: keep MAX_INVENTORY_SIZE >= 3 for cargo tests to be valid
-const MAX_INVENTORY_SIZE: usize = 3; // TODO: same for row/shelf/zone?
-
-// TODO: implement safeguards to Slot::new (e.g. MAX_INVENTORY_SIZE checks)
-#[derive(Hash, PartialEq, Eq, Copy, Clone)]
-struct Slot {
-    row: usize,
-    shelf: usize,
-    zone: usize,
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, MultiSelect, Select};
+use rust_intro_course_project::csv_io::ImportMode;
+use rust_intro_course_project::filters::{
+    AllowIds, BanQuality, IntakeQuota, IntakeScope, LimitItemQuantity, LimitOverSized, MaxOccupancy,
+    MinShelfLife, Quotas, UniqueNames, Warn,
+};
+use rust_intro_course_project::rules::Rule;
+#[cfg(feature = "alloc-greedy")]
+use rust_intro_course_project::GreedyAllocator;
+#[cfg(feature = "alloc-round-robin")]
+use rust_intro_course_project::RoundRobinAllocator;
+#[cfg(feature = "alloc-best-fit")]
+use rust_intro_course_project::BestFitAllocator;
+use rust_intro_course_project::{
+    Allocator, Filter, FilterVerdict, Item, Journal, Manager, ManagerError, Quality, QualityKind, Reservation,
+    Slot, WarehouseLayout,
+};
+use std::mem::discriminant;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+const SNAPSHOT_PATH: &str = "warehouse.json";
+const JOURNAL_PATH: &str = "warehouse.journal";
+const RECOVERY_BASE_PATH: &str = "warehouse.base.json"; // state the journal is replayed on
+const CSV_PATH: &str = "inventory.csv";
+const QUOTAS_PATH: &str = "quotas.csv";
+const RESERVATION_MINUTES: i64 = 30; // default expiration of a reservation
+const SUGGESTIONS: usize = 5; // slots offered to the operator when inserting
+
+fn pick_allocator() -> Allocator {
+    // only the strategies enabled through cargo features are offered
+    let mut options: Vec<(&str, Allocator)> = Vec::new();
+    #[cfg(feature = "alloc-greedy")]
+    options.push(("Greedy (closest to the base)", GreedyAllocator::default().into()));
+    #[cfg(feature = "alloc-round-robin")]
+    options.push(("Round-robin", RoundRobinAllocator::default().into()));
+    #[cfg(feature = "alloc-best-fit")]
+    options.push(("Best-fit (tightest free run)", BestFitAllocator::default().into()));
+    if options.len() == 1 {
+        return options.remove(0).1;
+    }
+
+    let default = options
+        .iter()
+        .position(|(_, a)| discriminant(a) == discriminant(&Allocator::default()))
+        .unwrap_or(0);
+    let selections: Vec<&str> = options.iter().map(|(name, _)| *name).collect();
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Pick your allocation method")
+        .default(default)
+        .items(&selections[..])
+        .interact()
+        .unwrap();
+    options.remove(selection).1
 }
 
-impl Slot {
-    fn new(row: usize, shelf: usize, zone: usize) -> Self {
-        Self { row, shelf, zone }
-    }
-    fn as_tuple(&self) -> (usize, usize, usize) {
-        (self.row, self.shelf, self.zone)
-    }
-
-    fn as_array(&self) -> [usize; 3] {
-        [self.row, self.shelf, self.zone]
+fn attach_journal(manager: &mut Manager<Allocator>) {
+    match Journal::open(JOURNAL_PATH) {
+        Ok(journal) => manager.attach_journal(journal),
+        Err(err) => println!("{} {}", style("Journaling is disabled:").red(), err),
     }
+}
 
-    fn distance(&self) -> usize {
-        // Manhattan distance
-        self.as_array().iter().sum()
+fn archive(path: &str) {
+    // keep files of the previous session around as <path>.old
+    if Path::new(path).exists()
+        && let Err(err) = fs::rename(path, format!("{path}.old"))
+    {
+        println!("{}", style(err).red());
     }
 }
 
-impl Display for Slot {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "[{}|{}|{}]", self.row, self.shelf, self.zone)
-    } // FIXME: choose a better string representation...
+fn rotate_journal(manager: &mut Manager<Allocator>) {
+    // start recording a new session on top of the current state
+    manager.detach_journal();
+    archive(JOURNAL_PATH);
+    attach_journal(manager);
 }
 
-impl Debug for Slot {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Display::fmt(self, f)
-    }
+fn input<T>(prompt: &str) -> T
+where
+    T: Clone + ToString + std::str::FromStr,
+    <T as std::str::FromStr>::Err: ToString,
+{
+    Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .interact_text()
+        .unwrap()
 }
 
-impl From<(usize, usize, usize)> for Slot {
-    fn from(value: (usize, usize, usize)) -> Self {
-        Self {
-            row: value.0,
-            shelf: value.1,
-            zone: value.2,
-        }
+fn input_duration(prompt: &str, default: Option<i64>, to_delta: fn(i64) -> Option<TimeDelta>) -> TimeDelta {
+    // reject values that do not fit in a TimeDelta (or a date that far from now) up front
+    let theme = ColorfulTheme::default();
+    let mut input = Input::with_theme(&theme)
+        .with_prompt(prompt)
+        .validate_with(|value: &i64| match to_delta(*value) {
+            Some(delta) if Local::now().checked_add_signed(delta).is_some()
+                && Local::now().checked_sub_signed(delta).is_some() => Ok(()),
+            _ => Err("Out of range"),
+        });
+    if let Some(default) = default {
+        input = input.default(default);
     }
+    to_delta(input.interact_text().unwrap()).unwrap() // checked by the validator
 }
 
-impl From<[usize; 3]> for Slot {
-    fn from(value: [usize; 3]) -> Self {
-        Self {
-            row: value[0],
-            shelf: value[1],
-            zone: value[2],
-        }
+fn prompt_item() -> Item {
+    let id: usize = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Input ID: ")
+        .interact_text()
+        .unwrap();
+    let name: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Input name: ")
+        .interact_text()
+        .unwrap();
+    let quantity: usize = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Input quantity: ")
+        .interact_text()
+        .unwrap();
+    let quality_selections = &[
+        "Normal",
+        "Over-sized",
+        "Fragile",
+    ];
+    let quality_selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Input quality: ")
+        .default(0)
+        .items(&quality_selections[..])
+        .interact()
+        .unwrap();
+    match quality_selection {
+        0 => Item::new(id, name.as_str(), quantity, Quality::Normal),
+        1 => {
+            let size: usize = Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Input size: ")
+                .interact_text()
+                .unwrap();
+            Item::new(id, name.as_str(), quantity, Quality::OverSized { size })
+        },
+        2 => {
+            let exp_date: String = Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Input expiration date (%Y-%m-%d %H:%M:%S): ")
+                .interact_text()
+                .unwrap();
+            let exp_date = NaiveDateTime::parse_from_str(
+                exp_date.as_str(), "%Y-%m-%d %H:%M:%S"
+            ).unwrap();
+            let exp_date = Local.from_local_datetime(&exp_date).unwrap();
+
+            let max_row: usize = Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Input max. row allowed: ")
+                .interact_text()
+                .unwrap();
+
+            Item::new(id, name.as_str(), quantity, Quality::Fragile { expiration_date: exp_date, max_row })
+        },
+        _ => todo!()
     }
 }
 
-#[derive(Clone, Eq, PartialEq)]
-enum Quality {
-    Fragile {
-        expiration_date: DateTime<Local>,
-        max_row: usize,
-    },
-    OverSized {
-        size: usize,
-    },
-    Normal,
-}
-
-impl Display for Quality {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Quality::Fragile { .. } => write!(f, "Fragile"),
-            Quality::OverSized { .. } => write!(f, "OverSized"),
-            Quality::Normal => write!(f, "Normal"),
-        }
+fn confirm_warnings(warnings: &[FilterVerdict]) -> bool {
+    // advisory filters: let the operator decide before committing
+    if warnings.is_empty() {
+        return true;
     }
-}
-
-impl Debug for Quality {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Quality::Fragile {
-                expiration_date,
-                max_row: max_dist,
-            } => write!(f, "Fragile ({}, {})", expiration_date, max_dist),
-            Quality::OverSized { size } => write!(f, "OverSized ({})", size),
-            Quality::Normal => write!(f, "Normal"),
-        }
+    for verdict in warnings {
+        println!("  {} {}", style(&verdict.filter).yellow(), style(&verdict.reason).yellow());
     }
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Insert the item anyway?")
+        .default(false)
+        .interact()
+        .unwrap()
 }
 
-#[derive(Clone)]
-struct Item {
-    id: usize,
-    name: String,
-    quantity: usize,
-    quality: Quality,
-    // additional fields
-    timestamp: Option<DateTime<Local>>,
-}
-
-impl Item {
-    fn new(id: usize, name: &str, quantity: usize, quality: Quality) -> Self {
-        Self {
-            id,
-            name: name.to_string(),
-            quantity,
-            quality,
-            timestamp: None,
-        }
-    }
-
-    fn update_timestamp(&mut self) {
-        self.timestamp = Some(Local::now());
+fn print_rejections(rejections: &[FilterVerdict]) {
+    println!("{}", style("Filters do not allow this item!").red());
+    for verdict in rejections {
+        println!("  {} {}", style(&verdict.filter).red(), verdict.reason);
     }
 }
 
-impl Display for Item {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let timestamp = self
-            .timestamp
-            .map(|t| t.format("%Y-%m-%d %H:%M:%S %:z").to_string())
-            .unwrap_or_else(|| "???".to_string());
-
-        match self.quality {
-            Quality::Fragile {
-                expiration_date,
-                max_row,
-            } => {
-                write!(
-                    f,
-                    "[Item {}: {}] [Qty: {}, {}] [Created at: {}] [Expires at: {}] [Must be stored at most at row {}]",
-                    self.id,
-                    self.name,
-                    self.quantity,
-                    self.quality,
-                    timestamp,
-                    expiration_date.format("%Y-%m-%d %H:%M:%S %:z"),
-                    max_row
-                )
+fn prompt_filter(layout: &WarehouseLayout) -> Option<Box<dyn Filter>> {
+    let kinds = &[
+        "Max. over-sized items",
+        "Max. units of an item ID",
+        "Ban over-sized items of a size",
+        "Allow only some IDs",
+        "Min. shelf life of fragile items",
+        "Max. occupancy",
+        "Max. pallets received per period",
+        "Quotas from a file",
+        "Warn when a name is already used by another ID",
+        "Custom rule",
+    ];
+    let kind = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Filter type")
+        .default(0)
+        .items(&kinds[..])
+        .interact()
+        .unwrap();
+    let filter: Box<dyn Filter> = match kind {
+        0 => Box::from(LimitOverSized::new(input("Max. over-sized items: "))),
+        1 => Box::from(LimitItemQuantity::new(input("Item ID: "), input("Max. units: "))),
+        2 => {
+            let size: usize = Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Size: ")
+                .default(layout.zones)
+                .interact_text()
+                .unwrap();
+            Box::from(BanQuality::new(Quality::OverSized { size }))
+        }
+        3 => {
+            let ids: String = input("Allowed IDs (comma separated): ");
+            match ids.split(',').map(|id| id.trim().parse::<usize>()).collect::<Result<Vec<_>, _>>() {
+                Ok(ids) => Box::from(AllowIds::new(ids)),
+                Err(err) => {
+                    println!("{}", style(err).red());
+                    return None;
+                }
             }
-            Quality::OverSized { size } => {
-                write!(
-                    f,
-                    "[Item {}: {}] [Qty: {}, {}] [Created at: {}] [Requires {} contiguous zones]",
-                    self.id, self.name, self.quantity, self.quality, timestamp, size
-                )
+        }
+        4 => Box::from(MinShelfLife::new(
+            input_duration("Min. days before expiration: ", None, TimeDelta::try_days).num_days(),
+        )),
+        5 => Box::from(MaxOccupancy::new(input("Max. occupancy (%): "), [], [])),
+        6 => {
+            let scopes = &["All items", "Item ID", "Normal", "OverSized", "Fragile"];
+            let scope = match Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("Count")
+                .default(0)
+                .items(&scopes[..])
+                .interact()
+                .unwrap()
+            {
+                0 => IntakeScope::All,
+                1 => IntakeScope::Id(input("Item ID: ")),
+                2 => IntakeScope::Quality(QualityKind::Normal),
+                3 => IntakeScope::Quality(QualityKind::OverSized),
+                _ => IntakeScope::Quality(QualityKind::Fragile),
+            };
+            let max_items = input("Max. pallets: ");
+            let period = input_duration("Period (hours): ", None, TimeDelta::try_hours);
+            Box::from(IntakeQuota::new(scope, max_items, period))
+        }
+        7 => {
+            let path: String = Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Path: ")
+                .default(QUOTAS_PATH.to_string())
+                .interact_text()
+                .unwrap();
+            match Quotas::load(&path) {
+                Ok(quotas) => Box::from(quotas),
+                Err(err) => {
+                    println!("{}", style(err).red());
+                    return None;
+                }
             }
-            Quality::Normal => {
-                write!(
-                    f,
-                    "[Item {}: {}] [Qty: {}, {}] [Created at: {}]",
-                    self.id, self.name, self.quantity, self.quality, timestamp
-                )
+        }
+        8 => Box::from(UniqueNames),
+        _ => {
+            let source: String = input("Rule (e.g. id in [0, 4] -> max_units 50): ");
+            match Rule::parse(&source) {
+                Ok(rule) => Box::from(rule),
+                Err(err) => {
+                    println!("{}", style(err.pretty(&source)).red());
+                    return None;
+                }
             }
         }
-    }
-}
-
-impl Debug for Item {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Display::fmt(self, f)
-    }
-}
-
-impl PartialEq for Item {
-    fn eq(&self, other: &Self) -> bool {
-        self.id == other.id
-            && self.name == other.name
-            && self.quantity == other.quantity
-            && self.quality == other.quality
-    }
-}
-
-#[derive(Debug)]
-struct Manager<A>
-where
-    A: AllocStrategy,
-{
-    inventory: HashMap<Slot, Item>,
-    allocator: A,
-    filters: Vec<Box<dyn Filter>>, // need dynamic dispatch to hold different impls of Filter
-
-    // reverse-maps
-    map_ids: HashMap<usize, usize>,       // id, count
-    map_names: HashMap<String, usize>,    // name, count
-    map_slots: HashMap<usize, Vec<Slot>>, // id, list of slots
-    // only used for Quality::Fragile items
-    map_dates: BTreeMap<DateTime<Local>, Vec<Slot>>, // date, list of ids
+    };
+    let warn_only = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Only warn instead of rejecting?")
+        .default(kind == 8)
+        .interact()
+        .unwrap();
+    Some(if warn_only { Box::from(Warn::new(filter)) } else { filter })
 }
 
-impl<A> Manager<A>
-where
-    A: AllocStrategy,
-{
-    fn new(allocator: A, filters: Vec<Box<dyn Filter>>) -> Manager<A> {
-        Manager {
-            inventory: HashMap::new(),
-            allocator,
-            filters,
-
-            map_ids: HashMap::new(),
-            map_names: HashMap::new(),
-            map_slots: HashMap::new(),
-            map_dates: BTreeMap::new(),
+fn manage_filters(manager: &mut Manager<Allocator>) {
+    loop {
+        let filters: Vec<(String, bool)> = manager
+            .filters()
+            .map(|(filter, enabled)| (filter.to_string(), enabled))
+            .collect();
+        if filters.is_empty() {
+            println!("{}", style("No filters").yellow());
         }
-    }
-
-    fn set_filters(&mut self, filters: Vec<Box<dyn Filter>>) {
-        self.filters = filters;
-    }
-
-    fn insert_filter(&mut self, filter: Box<dyn Filter>) {
-        self.filters.push(filter);
-    }
-
-    fn is_allowed_by_filters(&self, item: &Item) -> bool {
-        self.filters.iter().all(|f| f.filter(item, &self.inventory)) // short-circuits
-    }
-
-    fn insert_item(&mut self, item: Item) -> Result<(), ManagerError> {
-        if !self.is_allowed_by_filters(&item) {
-            return Err(ManagerError::FilteredItem {
-                item,
-                filters: self.filters.iter().map(|v| v.to_string()).collect(),
-            }); // short-circuit if some filter is triggered
+        for (name, enabled) in &filters {
+            let state = if *enabled { style("[on] ").green() } else { style("[off]").yellow() };
+            println!("  {} {}", state, name);
         }
 
-        let slot = self
-            .allocator
-            .alloc(&item, &self.inventory)
-            .ok_or_else(|| ManagerError::FailedAllocation {
-                allocator: self.allocator.to_string(),
-                item: item.clone(),
-            })?;
-
-        self._update_maps_on_insert(&slot, &item);
-        self._insert_item(slot, item);
-        Ok(())
-    }
-
-    fn _insert_item(&mut self, slot: Slot, mut item: Item) {
-        item.update_timestamp();
-        self.inventory.entry(slot).or_insert(item);
-    }
-
-    fn _update_maps_on_insert(&mut self, slot: &Slot, item: &Item) {
-        *self.map_ids.entry(item.id).or_insert(0) += 1;
-        *self.map_names.entry(item.name.clone()).or_insert(0) += 1;
-        self.map_slots.entry(item.id).or_insert(vec![]).push(*slot);
-
-        match item.quality {
-            Quality::Fragile {
-                expiration_date, ..
-            } => {
-                self.map_dates
-                    .entry(expiration_date.clone())
-                    .or_insert(vec![])
-                    .push(*slot);
+        let actions = &["Add a filter", "Remove a filter", "Enable/disable a filter", "Back"];
+        let action = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Manage filters")
+            .default(0)
+            .items(&actions[..])
+            .interact()
+            .unwrap();
+        if action == 0 {
+            if let Some(filter) = prompt_filter(manager.layout()) {
+                println!("{} {}", style("Added").green(), filter);
+                manager.insert_filter(filter);
             }
-            _ => {}
+            continue;
         }
-    }
-
-    fn get_item(&self, row: usize, shelf: usize, zone: usize) -> Option<&Item> {
-        let slot = Slot::from((row, shelf, zone));
-        self._get_item(&slot)
-    }
-
-    fn _get_item(&self, slot: &Slot) -> Option<&Item> {
-        self.inventory.get(slot)
-    }
-
-    fn remove_item(&mut self, row: usize, shelf: usize, zone: usize) {
-        let slot = Slot::from((row, shelf, zone));
-        if let Some(item) = self._remove_item(&slot) {
-            self._update_maps_on_remove(&slot, &item)
+        if action == 3 {
+            return;
         }
-    }
-
-    fn _remove_item(&mut self, slot: &Slot) -> Option<Item> {
-        self.inventory.remove(slot)
-    }
-
-    fn _update_maps_on_remove(&mut self, slot: &Slot, item: &Item) {
-        self.map_ids.entry(item.id).and_modify(|count| *count -= 1);
-        self.map_names
-            .entry(item.name.clone())
-            .and_modify(|count| *count -= 1);
-        self.map_slots
-            .entry(item.id)
-            .and_modify(|vec| vec.retain(|s| *s != *slot));
-
-        match item.quality {
-            Quality::Fragile {
-                expiration_date, ..
-            } => {
-                self.map_dates
-                    .entry(expiration_date)
-                    .and_modify(|vec| vec.retain(|s| *s != *slot));
-            }
-            _ => {}
+        if filters.is_empty() {
+            continue;
         }
 
-        // clean-up empty entries
-        // FIXME: inefficient, because iterates over HashMap when at most a single entry needs to
-        //        be cleaned up. Instead, it would be better to clean-up using Entry API just after
-        //        we are done updating the HashMaps
-        self.map_ids.retain(|_, count| *count != 0);
-        self.map_names.retain(|_, count| *count != 0);
-        self.map_slots.retain(|_, vec| !vec.is_empty());
-        self.map_dates.retain(|_, vec| !vec.is_empty());
-    }
-
-    fn ord_by_name(&self) -> Vec<&Item> {
-        // convert to Vec for O(N log(N)) sorting
-        let mut items: Vec<&Item> = self.inventory.values().collect();
-        items.sort_by(|a, b| a.name.cmp(&b.name));
-        items // sort refs to avoid copying (low memory footprint)
-    }
-
-    fn count_id(&self, id: usize) -> usize {
-        // TODO: should also return a bool to indicate count > 0?
-        // TODO: should return an Option or Result to indicate count = 0?
-        self.map_ids.get(&id).map_or(0, |v| *v)
-    }
-
-    fn count_name(&self, name: &str) -> usize {
-        // TODO: should also return a bool to indicate count > 0?
-        // TODO: should return an Option or Result to indicate count = 0?
-        self.map_names.get(name).map_or(0, |v| *v)
-    }
-
-    fn find_id(&self, id: usize) -> Option<&Vec<Slot>> {
-        // TODO: should also return a bool to indicate count > 0?
-        // TODO: should return an Option or Result to indicate count = 0?
-        self.map_slots.get(&id)
-    }
-
-    fn find_expired(&self, date: DateTime<Local>) -> Vec<Item> {
-        self.map_dates
-            .range(..=date)
-            .flat_map(|(_, ids)| ids)
-            .copied()
-            .map(|s| s.as_tuple())
-            .map(|(row, shelf, zone)| self.get_item(row, shelf, zone))
-            .filter(|opt| opt.is_some())
-            .map(|opt| opt.unwrap()) // safe to unwrap
-            .cloned()
-            .collect::<Vec<_>>()
+        let names: Vec<&str> = filters.iter().map(|(name, _)| name.as_str()).collect();
+        let index = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Pick a filter")
+            .default(0)
+            .items(&names[..])
+            .interact()
+            .unwrap();
+        let (name, enabled) = &filters[index];
+        let result = match action {
+            1 => manager.remove_filter(name).map(|_| "Removed"),
+            _ if *enabled => manager.disable_filter(name).map(|_| "Disabled"),
+            _ => manager.enable_filter(name).map(|_| "Enabled"),
+        };
+        match result {
+            Ok(done) => println!("{} {}", style(done).green(), name),
+            Err(err) => println!("{}", style(err).red()),
+        }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::{Item, MAX_INVENTORY_SIZE, Manager, Quality, Slot};
-    use crate::allocators::{RoundRobinAllocator, GreedyAllocator};
-    use crate::errors::ManagerError;
-    use chrono::{Local, NaiveDateTime, TimeZone};
-    use itertools::Itertools;
-
-    #[test]
-    fn test_manager() {
-        // FIXME: tbh this is not a good unit test because it relies on RoundRobin correctness
-        //        e.g. checking expected Slots after assigning items
-        //        Proper testing would require manually setting up the items in the desired slots
-        let exp_date =
-            NaiveDateTime::parse_from_str("2020-01-01 14:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
-        let exp_date = Local.from_local_datetime(&exp_date).unwrap(); // DateTime<Local>
-
-        // no filters, RoundRobin
-        let mut manager = Manager::new(RoundRobinAllocator::default(), Vec::new());
-        // let mut manager = Manager::new(GreedyAllocator {}, Vec::new());
-
-        let item0 = Item::new(0, "Flour", 10, Quality::Normal);
-        let item1 = Item::new(1, "Wood", 5, Quality::OverSized { size: 2 });
-        let item2 = Item::new(2, "Glass", 2, Quality::Fragile { expiration_date: exp_date, max_row: 1 });
-
-        manager.insert_item(item0.clone()).unwrap();  // Normal
-        manager.insert_item(item0.clone()).unwrap();  // Normal
-        manager.insert_item(item0.clone()).unwrap();  // Normal
-        manager.insert_item(item0.clone()).unwrap();  // Normal
-        manager.insert_item(item1.clone()).unwrap();  // OverSized
-        manager.insert_item(item0.clone()).unwrap();  // Normal
-        manager.insert_item(item2.clone()).unwrap();  // Fragile
-        manager.insert_item(item2.clone()).unwrap();  // Fragile
-        manager.insert_item(item0.clone()).unwrap();  // Normal
-        manager.insert_item(item2.clone()).unwrap();  // Fragile
-
-        {
-            let item0 = Item::new(0, "Flour", 10, Quality::Normal);
-            let item1 = Item::new(1, "Wood", 5, Quality::OverSized { size: 2 });
-            let item2 = Item::new(2, "Glass", 2, Quality::Fragile { expiration_date: exp_date, max_row: 1 });
-            let ordered = manager.ord_by_name();
-            assert_eq!(ordered.len(), 10);
-            assert!(&ordered[0..6].iter().all_equal());
-            assert!(&ordered[0..6].iter().all(|x| **x == item0));
-            assert!(&ordered[6..9].iter().all_equal());
-            assert!(&ordered[6..9].iter().all(|x| **x == item2));
-            assert!(&ordered[9..9].iter().all_equal());
-            assert!(&ordered[9..9].iter().all(|x| **x == item1));
+fn manage_reservations(manager: &mut Manager<Allocator>) {
+    loop {
+        for reservation in manager.expire_reservations() {
+            println!("{} {} {}", style("Expired:").yellow(), reservation.slot, reservation.item);
+        }
+        let pending: Vec<Reservation> = manager.reservations().into_iter().cloned().collect();
+        let labels: Vec<String> = pending
+            .iter()
+            .map(|reservation| {
+                format!(
+                    "{} {} (expires at {})",
+                    reservation.slot,
+                    reservation.item,
+                    reservation.expires_at.format("%Y-%m-%d %H:%M:%S")
+                )
+            })
+            .collect();
+        if labels.is_empty() {
+            println!("{}", style("No reservations").yellow());
+        }
+        for label in &labels {
+            println!("  {}", label);
         }
 
-        assert_eq!(manager.count_id(0), 6);
-        assert_eq!(manager.count_id(1), 1);
-        assert_eq!(manager.count_id(2), 3);
-
-        assert_eq!(manager.count_name("Flour"), 6);
-        assert_eq!(manager.count_name("Wood"), 1);
-        assert_eq!(manager.count_name("Glass"), 3);
-
-        let slot = manager.find_id(1).unwrap();
-        assert_eq!(slot.len(), 1);
-        let slot = slot[0];
-        assert_eq!(slot, Slot::from((0, 1, 1)));
+        let actions = &["Reserve a slot", "Commit a reservation", "Cancel a reservation", "Back"];
+        let action = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Manage reservations")
+            .default(0)
+            .items(&actions[..])
+            .interact()
+            .unwrap();
+        if action == 0 {
+            let item = prompt_item();
+            let timeout = input_duration("Expires after (minutes): ", Some(RESERVATION_MINUTES), TimeDelta::try_minutes);
+            match manager.reserve_item(item, timeout) {
+                Ok(reservation) => println!("{} {}", style("Reserved").green(), reservation.slot),
+                Err(err) => println!("{}", style(err).red()),
+            }
+            continue;
+        }
+        if action == 3 {
+            return;
+        }
+        if labels.is_empty() {
+            continue;
+        }
 
-        let expired = manager.find_expired(Local::now());
-        assert_eq!(expired.len(), 3);
-        assert!(expired.iter().all(|item| item == &item2));
+        let index = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Pick a reservation")
+            .default(0)
+            .items(&labels[..])
+            .interact()
+            .unwrap();
+        if action == 1 {
+            match manager.check_reservation(pending[index].slot) {
+                Ok(warnings) => {
+                    if !confirm_warnings(&warnings) {
+                        continue;
+                    }
+                },
+                Err(ManagerError::FilteredItem { filters, .. }) => {
+                    print_rejections(&filters);
+                    continue;
+                },
+                Err(err) => {
+                    println!("{}", style(err).red());
+                    continue;
+                },
+            }
+            match manager.commit_reservation(pending[index].slot) {
+                Ok(insertion) => {
+                    println!("{} {}", style("Item was inserted successfully at").green(), insertion.slot);
+                },
+                Err(err) => println!("{}", style(err).red()),
+            }
+        } else {
+            match manager.cancel_reservation(pending[index].slot) {
+                Ok(reservation) => println!("{} {}", style("Cancelled").green(), reservation.slot),
+                Err(err) => println!("{}", style(err).red()),
+            }
+        }
     }
 }
 
-
 fn main() {
-    // HARDCODED - CHANGE HERE
-    let allocator = RoundRobinAllocator::default();
-    // let allocator = GreedyAllocator::default();
+    // ALLOCATION STRATEGY
+    let allocator = pick_allocator();
+
+    // WAREHOUSE LAYOUT
+    let default_layout = WarehouseLayout::default();
+    let rows: usize = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Number of rows: ")
+        .default(default_layout.rows)
+        .interact_text()
+        .unwrap();
+    let shelves: usize = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Number of shelves per row: ")
+        .default(default_layout.shelves)
+        .interact_text()
+        .unwrap();
+    let zones: usize = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt("Number of zones per shelf: ")
+        .default(default_layout.zones)
+        .interact_text()
+        .unwrap();
+    let layout = WarehouseLayout::new(rows, shelves, zones);
 
     // PRESET FILTERS
-    let tmp_string = format!("Ban over-sized items with size {MAX_INVENTORY_SIZE}");
+    let tmp_string = format!("Ban over-sized items with size {}", layout.zones);
     let tmp = tmp_string.as_str();
     let multiselected = &[
         "Max. 1 over-sized item allowed",
         "Max. 2 over-sized item allowed",
         "Max. 50 units of item ID:0",
         tmp,
+        "Reject fragile items expiring within 3 days",
+        "Keep 10% of the zones free (fragile items exempt)",
+        "Max. 20 over-sized pallets received per day",
+        "Warn when a name is already used by another ID",
     ];
 
-    let mut filters = Vec::<Box<dyn Filter>>::new();
-    filters.push(Box::from(LimitOverSized::new(1)));
-    filters.push(Box::from(LimitOverSized::new(2)));
-    filters.push(Box::from(LimitItemQuantity::new(0, 50)));
-    filters.push(Box::from(BanQuality::new(Quality::OverSized {
-        size: MAX_INVENTORY_SIZE,
-    })));
+    let mut filters: Vec<Box<dyn Filter>> = vec![
+        Box::from(LimitOverSized::new(1)),
+        Box::from(LimitOverSized::new(2)),
+        Box::from(LimitItemQuantity::new(0, 50)),
+        Box::from(BanQuality::new(Quality::OverSized {
+            size: layout.zones,
+        })),
+        Box::from(MinShelfLife::new(3)),
+        Box::from(MaxOccupancy::new(90, [], [QualityKind::Fragile])),
+        Box::from(IntakeQuota::new(
+            IntakeScope::Quality(QualityKind::OverSized),
+            20,
+            TimeDelta::days(1),
+        )),
+        Box::from(Warn::new(Box::from(UniqueNames))),
+    ];
 
     // CHOOSE FILTERS FROM PRESETS
-    let defaults = &[false, false, false, false];
+    let defaults = &[false; 8];
     let selections = MultiSelect::with_theme(&ColorfulTheme::default())
         .with_prompt("Pick your filters")
         .items(&multiselected[..])
@@ -475,34 +482,130 @@ fn main() {
         .interact()
         .unwrap();
 
-    if !selections.is_empty() {
-        let keep: HashSet<usize> = selections.iter().copied().collect();
-        let mut i = 0;
-        filters.retain(|_| {
-            let keep_it = keep.contains(&i);
-            i += 1;
-            keep_it
-        });
+    let keep: HashSet<usize> = selections.iter().copied().collect();
+    let mut i = 0;
+    filters.retain(|_| {
+        let keep_it = keep.contains(&i);
+        i += 1;
+        keep_it
+    });
+
+    // QUOTA TABLE
+    if Path::new(QUOTAS_PATH).exists()
+        && Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Enforce the quotas in {}?", QUOTAS_PATH))
+            .default(true)
+            .interact()
+            .unwrap()
+    {
+        match Quotas::load(QUOTAS_PATH) {
+            Ok(quotas) => filters.push(Box::from(quotas)),
+            Err(err) => println!("{}", style(err).red()),
+        }
+    }
+
+    // CUSTOM RULES
+    while Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Add a custom rule?")
+        .default(false)
+        .interact()
+        .unwrap()
+    {
+        let source: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Rule (e.g. id in [0, 4] -> max_units 50): ")
+            .interact_text()
+            .unwrap();
+        match Rule::parse(&source) {
+            Ok(rule) => filters.push(Box::from(rule)),
+            Err(err) => println!("{}", style(err.pretty(&source)).red()),
+        }
     }
 
     // INIT MANAGER
-    let mut manager = Manager::new(allocator, filters);
+    let mut manager = Manager::new(layout, allocator, filters);
+
+    // CRASH RECOVERY
+    let mut recovered = false;
+    if Path::new(JOURNAL_PATH).exists()
+        && Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("Recover the previous session from the journal?")
+            .default(true)
+            .interact()
+            .unwrap()
+    {
+        // the journal only holds the events since the session started from its base
+        let base = if Path::new(RECOVERY_BASE_PATH).exists() {
+            Manager::load_snapshot(RECOVERY_BASE_PATH).map(Some)
+        } else {
+            Ok(None) // the session started from an empty warehouse
+        };
+        match base {
+            Ok(base) => {
+                if let Some(loaded) = base {
+                    manager = loaded;
+                }
+                match manager.replay_journal(JOURNAL_PATH) {
+                    Ok(count) => println!("{} {} events", style("Replayed").green(), count),
+                    Err(err) => {
+                        // the state is incomplete, and appending to a broken journal would make it worse
+                        println!("{} {}", style("Could not replay the journal:").red(), err);
+                        println!(
+                            "{}",
+                            style(format!(
+                                "{JOURNAL_PATH} was left untouched, restart without recovering to start a new session"
+                            ))
+                            .red()
+                        );
+                        return;
+                    },
+                }
+                attach_journal(&mut manager);
+                recovered = true;
+            },
+            Err(err) => {
+                // replaying on anything else would silently lose the items stored before the session
+                println!("{} {}", style("Could not load the recovery base:").red(), err);
+                if !Confirm::with_theme(&ColorfulTheme::default())
+                    .with_prompt(format!("Start a new session? (the journal is kept as {JOURNAL_PATH}.old)"))
+                    .default(false)
+                    .interact()
+                    .unwrap()
+                {
+                    return;
+                }
+            },
+        }
+    }
+    if !recovered {
+        // new session: the previous recovery base is no longer valid (user snapshots are kept)
+        archive(RECOVERY_BASE_PATH);
+        rotate_journal(&mut manager);
+    }
 
     // CLI
     loop {
         let selections = &[
             "Insert item",
+            "Place item at slot",
             "Remove item",
             "Locate item",
             "Count items by ID",
             "Count items by name",
             "List expired items",
             "List all items",
+            "Save snapshot",
+            "Load snapshot",
+            "Export CSV",
+            "Import CSV",
+            "Switch allocation method",
+            "Explain allocation (dry run)",
+            "Manage filters",
+            "Manage reservations",
             "Quit",
         ];
 
         let selection = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Pick your allocation method")
+            .with_prompt("Pick an action")
             .default(0)
             .items(&selections[..])
             .interact()
@@ -510,73 +613,89 @@ fn main() {
 
         match selection {
             0 => {
-                let id: usize = Input::with_theme(&ColorfulTheme::default())
-                    .with_prompt("Input ID: ")
-                    .interact_text()
-                    .unwrap();
-                let name: String = Input::with_theme(&ColorfulTheme::default())
-                    .with_prompt("Input name: ")
-                    .interact_text()
-                    .unwrap();
-                let quantity: usize = Input::with_theme(&ColorfulTheme::default())
-                    .with_prompt("Input quantity: ")
-                    .interact_text()
-                    .unwrap();
-                let quality_selections = &[
-                    "Normal",
-                    "Over-sized",
-                    "Fragile",
-                ];
-                let quality_selection = Select::with_theme(&ColorfulTheme::default())
-                    .with_prompt("Input quality: ")
-                    .default(0)
-                    .items(&quality_selections[..])
-                    .interact()
-                    .unwrap();
-                let item = match quality_selection {
-                    0 => Item::new(id, name.as_str(), quantity, Quality::Normal),
-                    1 => {
-                        let size: usize = Input::with_theme(&ColorfulTheme::default())
-                            .with_prompt("Input size: ")
-                            .interact_text()
-                            .unwrap();
-                        Item::new(id, name.as_str(), quantity, Quality::OverSized { size })
+                let item = prompt_item();
+                match manager.is_allowed_by_filters(&item) {
+                    Ok(warnings) => {
+                        if !confirm_warnings(&warnings) {
+                            continue;
+                        }
                     },
-                    2 => {
-                        let exp_date: String = Input::with_theme(&ColorfulTheme::default())
-                            .with_prompt("Input expiration date (%Y-%m-%d %H:%M:%S): ")
-                            .interact_text()
-                            .unwrap();
-                        let exp_date = NaiveDateTime::parse_from_str(
-                            exp_date.as_str(), "%Y-%m-%d %H:%M:%S"
-                        ).unwrap();
-                        let exp_date = Local.from_local_datetime(&exp_date).unwrap();
-
-                        let max_row: usize = Input::with_theme(&ColorfulTheme::default())
-                            .with_prompt("Input max. row allowed: ")
-                            .interact_text()
-                            .unwrap();
-
-                        Item::new(id, name.as_str(), quantity, Quality::Fragile { expiration_date: exp_date, max_row })
+                    Err(rejections) => {
+                        print_rejections(&rejections);
+                        continue;
                     },
-                    _ => todo!()
+                }
+                // let the operator override the allocator with one of the best slots
+                let suggestions = manager.suggest_slots(&item, SUGGESTIONS);
+                let mut choices = vec![format!("Let the allocator decide ({})", manager.allocator())];
+                choices.extend(suggestions.iter().map(|suggestion| suggestion.to_string()));
+                let choice = if suggestions.is_empty() {
+                    0
+                } else {
+                    Select::with_theme(&ColorfulTheme::default())
+                        .with_prompt("Pick a slot")
+                        .default(0)
+                        .items(&choices)
+                        .interact()
+                        .unwrap()
+                };
+                let result = match choice {
+                    0 => manager.insert_item(item),
+                    _ => manager.place_item_at(suggestions[choice - 1].slot, item),
                 };
-                let result = manager.insert_item(item);
                 match result {
-                    Ok(_) => {
-                        println!("{}", style("Item was inserted successfully!").green());
+                    Ok(insertion) => {
+                        println!("{} {}", style("Item was inserted successfully at").green(), insertion.slot);
                     },
-                    Err(ManagerError::FilteredItem { .. }) => {
-                        println!("{}", style("Filters do not allow this item!").red());
+                    Err(ManagerError::FilteredItem { filters, .. }) => {
+                        print_rejections(&filters);
                     },
-                    Err(ManagerError::FailedAllocation { .. }) => {
+                    Err(ManagerError::FailedAllocation { diagnostics, .. }) => {
                         println!("{}", style("Allocator could not find a suitable slot for this item!").red());
+                        println!("  {}", diagnostics);
                     },
-                    _ => todo!()
+                    Err(err) => {
+                        println!("{}", style(err).red());
+                    }
                 }
 
             },
             1 => {
+                let item = prompt_item();
+                let row: usize = input("Input row: ");
+                let shelf: usize = input("Input shelf: ");
+                let zone: usize = input("Input zone: ");
+                let slot = match Slot::new(row, shelf, zone, manager.layout()) {
+                    Ok(slot) => slot,
+                    Err(err) => {
+                        println!("{}", style(err).red());
+                        continue;
+                    }
+                };
+                match manager.is_allowed_by_filters(&item) {
+                    Ok(warnings) => {
+                        if !confirm_warnings(&warnings) {
+                            continue;
+                        }
+                    },
+                    Err(rejections) => {
+                        print_rejections(&rejections);
+                        continue;
+                    },
+                }
+                match manager.place_item_at(slot, item) {
+                    Ok(insertion) => {
+                        println!("{} {}", style("Item was placed at").green(), insertion.slot);
+                    },
+                    Err(ManagerError::FilteredItem { filters, .. }) => {
+                        print_rejections(&filters);
+                    },
+                    Err(err) => {
+                        println!("{}", style(err).red());
+                    }
+                }
+            },
+            2 => {
                 let row: usize = Input::with_theme(&ColorfulTheme::default())
                     .with_prompt("Input row: ")
                     .interact_text()
@@ -589,9 +708,16 @@ fn main() {
                     .with_prompt("Input zone: ")
                     .interact_text()
                     .unwrap();
-                manager.remove_item(row, shelf, zone);
+                match manager.remove_item(row, shelf, zone) {
+                    Ok(item) => {
+                        println!("{} {}", style("Removed: ").green(), item);
+                    },
+                    Err(err) => {
+                        println!("{}", style(err).red());
+                    }
+                }
             },
-            2 => {
+            3 => {
                 let id: usize = Input::with_theme(&ColorfulTheme::default())
                     .with_prompt("Input ID: ")
                     .interact_text()
@@ -605,8 +731,12 @@ fn main() {
                         println!("{}", style("Not found!").red());
                     }
                 }
+                let reserved = manager.find_reserved_id(id);
+                if !reserved.is_empty() {
+                    println!("{} {:#?}", style("Reserved at: ").yellow(), reserved);
+                }
             },
-            3 => {
+            4 => {
                 let id: usize = Input::with_theme(&ColorfulTheme::default())
                     .with_prompt("Input ID: ")
                     .interact_text()
@@ -614,7 +744,7 @@ fn main() {
                 let count = manager.count_id(id);
                 println!("{} {}", style("Number of occurrences: ").green(), count);
             },
-            4 => {
+            5 => {
                 let name: String = Input::with_theme(&ColorfulTheme::default())
                     .with_prompt("Input name: ")
                     .interact_text()
@@ -622,7 +752,7 @@ fn main() {
                 let count = manager.count_name(name.as_str());
                 println!("{} {}", style("Number of occurrences: ").green(), count);
             },
-            5 => {
+            6 => {
                 let exp_date: String = Input::with_theme(&ColorfulTheme::default())
                     .with_prompt("Input date (%Y-%m-%d %H:%M:%S): ")
                     .default(Local::now().format("%Y-%m-%d %H:%M:%S").to_string())
@@ -635,11 +765,105 @@ fn main() {
                 let expired = manager.find_expired(exp_date);
                 println!("{} {:#?}", style("Expired items: ").red(), expired);
             },
-            6 => {
+            7 => {
                 let all_items = manager.ord_by_name();
                 println!("{} {:#?}", style("All items: ").green(), all_items);
+                let reservations = manager.reservations();
+                if !reservations.is_empty() {
+                    println!("{} {:#?}", style("Reservations: ").yellow(), reservations);
+                }
             },
-            7 => {
+            8 => {
+                let path: String = Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Snapshot file: ")
+                    .default(SNAPSHOT_PATH.to_string())
+                    .interact_text()
+                    .unwrap();
+                match manager.save_snapshot(&path) {
+                    Ok(_) => println!("{} {}", style("Saved snapshot to").green(), path),
+                    Err(err) => println!("{}", style(err).red()),
+                }
+            },
+            9 => {
+                let path: String = Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Snapshot file: ")
+                    .default(SNAPSHOT_PATH.to_string())
+                    .interact_text()
+                    .unwrap();
+                match Manager::load_snapshot(&path) {
+                    Ok(loaded) => {
+                        manager = loaded;
+                        println!("{} {} ({})", style("Loaded snapshot from").green(), path, manager.layout());
+                        // the loaded state becomes the new recovery base
+                        if let Err(err) = manager.save_snapshot(RECOVERY_BASE_PATH) {
+                            println!("{}", style(err).red());
+                        }
+                        rotate_journal(&mut manager);
+                    },
+                    Err(err) => println!("{}", style(err).red()),
+                }
+            },
+            10 => {
+                let path: String = Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("CSV file: ")
+                    .default(CSV_PATH.to_string())
+                    .interact_text()
+                    .unwrap();
+                match manager.export_csv_file(&path) {
+                    Ok(_) => println!("{} {}", style("Exported inventory to").green(), path),
+                    Err(err) => println!("{}", style(err).red()),
+                }
+            },
+            11 => {
+                let path: String = Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt("CSV file: ")
+                    .default(CSV_PATH.to_string())
+                    .interact_text()
+                    .unwrap();
+                let mode_selections = &[
+                    "At the slots given in the file",
+                    "Through the allocator",
+                ];
+                let mode = match Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Place items: ")
+                    .default(0)
+                    .items(&mode_selections[..])
+                    .interact()
+                    .unwrap()
+                {
+                    0 => ImportMode::AtSlots,
+                    _ => ImportMode::Allocate,
+                };
+                match manager.import_csv_file(&path, mode) {
+                    Ok(report) => {
+                        println!("{} {}", style("Imported items: ").green(), report.imported.len());
+                        for (line, err) in report.rejected {
+                            println!("{} {}", style(format!("Line {line}:")).red(), err);
+                        }
+                        for (line, verdict) in report.warnings {
+                            println!("{} {}", style(format!("Line {line}:")).yellow(), style(verdict).yellow());
+                        }
+                    },
+                    Err(err) => println!("{}", style(err).red()),
+                }
+            },
+            12 => {
+                println!("{} {}", style("Current allocation method:").green(), manager.allocator());
+                let previous = manager.set_allocator(pick_allocator());
+                println!("{} {} -> {}", style("Switched").green(), previous, manager.allocator());
+            },
+            13 => {
+                let item = prompt_item();
+                let explanation = manager.explain_allocation(&item);
+                match explanation.slot {
+                    Some(slot) => println!("{} {}", style("Would be stored at").green(), slot),
+                    None => println!("{}", style("No suitable slot for this item").red()),
+                }
+                println!("  {}", explanation.diagnostics);
+            },
+            14 => manage_filters(&mut manager),
+            15 => manage_reservations(&mut manager),
+            16 => {
                 if Confirm::with_theme(&ColorfulTheme::default())
                     .with_prompt("Do you really want to quit?")
                     .interact()
@@ -653,4 +877,4 @@ fn main() {
             _ => unimplemented!()
         };
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,303 @@
+use crate::{
+    AllocStrategy, FilterVerdict, Insertion, InventoryView, Item, Manager, ManagerError, QualityKind, Slot,
+    WarehouseLayout,
+};
+use chrono::{DateTime, Local, TimeDelta};
+use std::collections::HashMap;
+
+/// Slot held for an incoming item until it is committed, cancelled or expires.
+///
+/// Reserved zones count as occupied for allocations, placements and
+/// [`InventoryView`](crate::InventoryView) queries, but the item is not part of the inventory
+/// (nor of snapshots and the journal) until the reservation is committed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reservation {
+    pub slot: Slot,
+    pub item: Item,
+    pub expires_at: DateTime<Local>,
+}
+
+impl<A> Manager<A>
+where
+    A: AllocStrategy,
+{
+    /// Checks `item` against the filters and holds a slot for it during `timeout`.
+    ///
+    /// Filters are checked again when the reservation is committed.
+    pub fn reserve_item(&mut self, item: Item, timeout: TimeDelta) -> Result<Reservation, ManagerError> {
+        self.expire_reservations();
+        self._check_size(&item)?;
+        self._check_filters(&item)?;
+        let slot = self._alloc(&item)?;
+        let reservation = Reservation {
+            slot,
+            item,
+            expires_at: self.clock.now() + timeout,
+        };
+        self._hold(&reservation);
+        Ok(reservation)
+    }
+
+    /// Stores the reserved item at its slot, see [`Manager::place_item_at`].
+    ///
+    /// If the item is rejected (e.g. by a filter), the reservation is kept so it can be cancelled.
+    pub fn commit_reservation(&mut self, slot: Slot) -> Result<Insertion, ManagerError> {
+        let reservation = self.cancel_reservation(slot)?;
+        self.place_item_at(slot, reservation.item.clone())
+            .inspect_err(|_| self._hold(&reservation))
+    }
+
+    /// Warnings that [`commit_reservation`](Manager::commit_reservation) would return, without
+    /// storing the item (e.g. to ask the operator before committing).
+    ///
+    /// Filters and placement rules see the zones of the reservation as free, like when it is
+    /// committed.
+    pub fn check_reservation(&self, slot: Slot) -> Result<Vec<FilterVerdict>, ManagerError> {
+        let reservation = self
+            .reservations
+            .get(&slot)
+            .filter(|reservation| reservation.expires_at > self.clock.now())
+            .ok_or(ManagerError::UnknownReservation { slot })?;
+        let view = WithoutReservation {
+            manager: self,
+            reservation,
+        };
+        let warnings = self
+            ._evaluate_filters(&reservation.item, &view)
+            .map_err(|filters| ManagerError::FilteredItem {
+                item: reservation.item.clone(),
+                filters,
+            })?;
+        let occupancy: HashMap<Slot, Slot> = self
+            .map_occupancy
+            .iter()
+            .filter(|(_, head)| **head != slot)
+            .map(|(covered, head)| (*covered, *head))
+            .collect();
+        self._check_placement_in(&slot, &reservation.item, &occupancy)?;
+        Ok(warnings)
+    }
+
+    /// Frees a reserved slot, returning the reservation.
+    pub fn cancel_reservation(&mut self, slot: Slot) -> Result<Reservation, ManagerError> {
+        self.expire_reservations();
+        let reservation = self
+            .reservations
+            .remove(&slot)
+            .ok_or(ManagerError::UnknownReservation { slot })?;
+        for covered in reservation.item.covered_slots(&slot) {
+            self.map_occupancy.remove(&covered);
+        }
+        Ok(reservation)
+    }
+
+    /// Frees the reservations that are past their expiration time, returning them.
+    pub fn expire_reservations(&mut self) -> Vec<Reservation> {
+        let now = self.clock.now();
+        let expired: Vec<Slot> = self
+            .reservations
+            .values()
+            .filter(|reservation| reservation.expires_at <= now)
+            .map(|reservation| reservation.slot)
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|slot| self.reservations.remove(&slot))
+            .inspect(|reservation| {
+                for covered in reservation.item.covered_slots(&reservation.slot) {
+                    self.map_occupancy.remove(&covered);
+                }
+            })
+            .collect()
+    }
+
+    /// Pending reservations (not yet expired), ordered by slot.
+    pub fn reservations(&self) -> Vec<&Reservation> {
+        let now = self.clock.now();
+        let mut reservations: Vec<&Reservation> = self
+            .reservations
+            .values()
+            .filter(|reservation| reservation.expires_at > now)
+            .collect();
+        reservations.sort_by_key(|reservation| reservation.slot.as_tuple());
+        reservations
+    }
+
+    /// Returns the pending reservation holding the given zone, any zone covered by an
+    /// OverSized item works.
+    pub fn get_reservation(&self, row: usize, shelf: usize, zone: usize) -> Result<Option<&Reservation>, ManagerError> {
+        let slot = Slot::new(row, shelf, zone, &self.layout)?;
+        Ok(self
+            .map_occupancy
+            .get(&slot)
+            .and_then(|head| self.reservations.get(head))
+            .filter(|reservation| reservation.expires_at > self.clock.now()))
+    }
+
+    /// Slots reserved for items with the given id, ordered by slot.
+    pub fn find_reserved_id(&self, id: usize) -> Vec<Slot> {
+        self.reservations()
+            .into_iter()
+            .filter(|reservation| reservation.item.id == id)
+            .map(|reservation| reservation.slot)
+            .collect()
+    }
+
+    fn _hold(&mut self, reservation: &Reservation) {
+        for covered in reservation.item.covered_slots(&reservation.slot) {
+            self.map_occupancy.insert(covered, reservation.slot);
+        }
+        self.reservations.insert(reservation.slot, reservation.clone());
+    }
+}
+
+/// Inventory as it will be once `reservation` is committed (its zones are not counted yet).
+struct WithoutReservation<'a, A: AllocStrategy> {
+    manager: &'a Manager<A>,
+    reservation: &'a Reservation,
+}
+
+impl<A> InventoryView for WithoutReservation<'_, A>
+where
+    A: AllocStrategy,
+{
+    fn layout(&self) -> &WarehouseLayout {
+        self.manager.layout()
+    }
+
+    fn item_count(&self) -> usize {
+        self.manager.item_count()
+    }
+
+    fn count_id(&self, id: usize) -> usize {
+        self.manager.count_id(id)
+    }
+
+    fn units_id(&self, id: usize) -> usize {
+        self.manager.units_id(id)
+    }
+
+    fn count_name(&self, name: &str) -> usize {
+        self.manager.count_name(name)
+    }
+
+    fn units_name(&self, name: &str) -> usize {
+        self.manager.units_name(name)
+    }
+
+    fn count_name_id(&self, name: &str, id: usize) -> usize {
+        self.manager.count_name_id(name, id)
+    }
+
+    fn count_quality(&self, kind: QualityKind) -> usize {
+        self.manager.count_quality(kind)
+    }
+
+    fn occupied_zones(&self) -> usize {
+        let reserved = self.reservation.item.covered_slots(&self.reservation.slot).count();
+        self.manager.occupied_zones() - reserved
+    }
+
+    fn is_occupied(&self, slot: &Slot) -> bool {
+        self.manager.map_occupancy.get(slot).is_some_and(|head| *head != self.reservation.slot)
+    }
+
+    fn now(&self) -> DateTime<Local> {
+        self.manager.now()
+    }
+
+    fn items(&self) -> Box<dyn Iterator<Item = (&Slot, &Item)> + '_> {
+        self.manager.items()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::allocators::DefaultAllocator;
+    use crate::errors::ManagerError;
+    use crate::filters::{Filter, LimitItemQuantity, MaxOccupancy, Warn};
+    use crate::{FixedClock, InventoryView, Item, Manager, Quality, Slot, WarehouseLayout};
+    use chrono::{Local, TimeDelta};
+
+    #[test]
+    fn test_reservations() {
+        let now = Local::now();
+        let layout = WarehouseLayout::new(3, 3, 3);
+        let filters: Vec<Box<dyn Filter>> = vec![Box::from(LimitItemQuantity::new(2, 10))];
+        let mut manager = Manager::new(layout, DefaultAllocator::default(), filters);
+        manager.set_clock(Box::new(FixedClock::new(now)));
+
+        let wood = Item::new(0, "Wood", 5, Quality::OverSized { size: 2 });
+        let reservation = manager.reserve_item(wood.clone(), TimeDelta::minutes(10)).unwrap();
+        assert_eq!(reservation.slot, Slot::from((0, 0, 0)));
+        assert_eq!(reservation.expires_at, now + TimeDelta::minutes(10));
+
+        // reserved zones are taken, but nothing is stored yet
+        let flour = Item::new(1, "Flour", 1, Quality::Normal);
+        let slot = manager.insert_item(flour.clone()).unwrap().slot;
+        assert!(wood.covered_slots(&reservation.slot).all(|covered| covered != slot));
+        assert!(manager.place_item_at(Slot::from((0, 0, 1)), flour.clone()).is_err_and(|err| matches!(
+            err,
+            ManagerError::SlotReserved { head, .. } if head == Slot::from((0, 0, 0))
+        )));
+        assert!(manager.get_item(0, 0, 1).unwrap().is_none());
+        assert!(manager.remove_item(0, 0, 1).is_err_and(|err| matches!(err, ManagerError::NotFound { .. })));
+        assert_eq!(manager.get_reservation(0, 0, 1).unwrap(), Some(&reservation));
+        assert_eq!(manager.find_reserved_id(0), vec![Slot::from((0, 0, 0))]);
+        assert_eq!(manager.count_id(0), 0);
+        assert_eq!(manager.occupied_zones(), 3);
+        assert!(manager.reserve_item(Item::new(2, "Nails", 99, Quality::Normal), TimeDelta::minutes(1)).is_err_and(
+            |err| matches!(err, ManagerError::FilteredItem { .. })
+        ));
+
+        // committing stores the item at the reserved slot
+        manager.set_clock(Box::new(FixedClock::new(now + TimeDelta::minutes(5))));
+        let insertion = manager.commit_reservation(reservation.slot).unwrap();
+        assert_eq!(insertion.slot, Slot::from((0, 0, 0)));
+        let stored = manager.get_item(0, 0, 1).unwrap().unwrap();
+        assert_eq!(stored.timestamp(), Some(now + TimeDelta::minutes(5)));
+        assert!(manager.reservations().is_empty());
+        assert!(manager.commit_reservation(reservation.slot).is_err_and(|err| matches!(
+            err,
+            ManagerError::UnknownReservation { .. }
+        )));
+
+        // cancelling frees the slot
+        let reservation = manager.reserve_item(flour.clone(), TimeDelta::minutes(10)).unwrap();
+        let (row, shelf, zone) = reservation.slot.as_tuple();
+        assert_eq!(manager.cancel_reservation(reservation.slot).unwrap(), reservation);
+        assert!(manager.get_reservation(row, shelf, zone).unwrap().is_none());
+        assert_eq!(manager.occupied_zones(), 3);
+
+        // stale reservations expire
+        let reservation = manager.reserve_item(flour, TimeDelta::minutes(10)).unwrap();
+        manager.set_clock(Box::new(FixedClock::new(now + TimeDelta::minutes(20))));
+        assert!(manager.reservations().is_empty());
+        let (row, shelf, zone) = reservation.slot.as_tuple();
+        assert!(manager.get_reservation(row, shelf, zone).unwrap().is_none());
+        assert_eq!(manager.expire_reservations(), vec![reservation.clone()]);
+        assert!(!manager.is_occupied(&reservation.slot));
+        assert!(manager.commit_reservation(reservation.slot).is_err());
+    }
+
+    #[test]
+    fn test_check_reservation() {
+        // 27 zones, warn above 2
+        let filters: Vec<Box<dyn Filter>> = vec![Box::from(Warn::new(Box::from(MaxOccupancy::new(10, [], []))))];
+        let mut manager = Manager::new(WarehouseLayout::new(3, 3, 3), DefaultAllocator::default(), filters);
+        let wood = Item::new(0, "Wood", 5, Quality::OverSized { size: 2 });
+        let reservation = manager.reserve_item(wood.clone(), TimeDelta::minutes(10)).unwrap();
+
+        // the reserved zones are not counted twice, nor seen as taken
+        assert_eq!(manager.is_allowed_by_filters(&wood).unwrap().len(), 1);
+        assert!(manager.check_reservation(reservation.slot).unwrap().is_empty());
+        assert_eq!(manager.reservations(), vec![&reservation]);
+        manager.insert_item(Item::new(1, "Flour", 1, Quality::Normal)).unwrap();
+        assert_eq!(manager.check_reservation(reservation.slot).unwrap().len(), 1);
+        assert_eq!(manager.commit_reservation(reservation.slot).unwrap().warnings.len(), 1);
+        assert!(manager.check_reservation(reservation.slot).is_err_and(|err| matches!(
+            err,
+            ManagerError::UnknownReservation { .. }
+        )));
+    }
+}
@@ -0,0 +1,681 @@
+//! Small expression language for admission rules, so filters can be written without recompiling.
+//!
+//! ```text
+//! quality == OverSized && oversized_count < 2
+//! id in [0, 4, 7] -> max_units 50
+//! !(name == "Glass" || max_row > 1)
+//! ```
+//!
+//! - item fields: `id`, `name`, `quantity`, `quality`, `size` (zones), `max_row` (Fragile only)
+//! - inventory aggregates: `item_count`, `normal_count`, `oversized_count`, `fragile_count`,
+//!   `id_count`, `id_units`, `name_count` (the last three are about items with the same id/name)
+//! - comparisons: `==`, `!=`, `<`, `<=`, `>`, `>=`, `in [..]`
+//! - logic: `!`, `&&`, `||`, `->` (implication, lowest precedence), parentheses
+//! - `max_units N`: the total units of the item's id, including the new item, are at most `N`
+
+use crate::filters::{Filter, FilterConfig};
+use crate::view::InventoryView;
+use crate::{Item, Quality, QualityKind};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display};
+use thiserror::Error;
+
+/// Error found while parsing a rule, `column` is 1-based.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("column {column}: {message}")]
+pub struct RuleParseError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl RuleParseError {
+    fn new(column: usize, message: impl Into<String>) -> Self {
+        Self {
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// Source line with a caret under the offending column.
+    pub fn pretty(&self, source: &str) -> String {
+        format!(
+            "{}\n{}^ {}",
+            source,
+            " ".repeat(self.column.saturating_sub(1)),
+            self.message
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(usize),
+    Text(String),
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    And,
+    Or,
+    Not,
+    Implies,
+    Cmp(CmpOp),
+    End,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Ident(name) => write!(f, "`{}`", name),
+            Token::Number(n) => write!(f, "`{}`", n),
+            Token::Text(text) => write!(f, "{:?}", text),
+            Token::LParen => write!(f, "`(`"),
+            Token::RParen => write!(f, "`)`"),
+            Token::LBracket => write!(f, "`[`"),
+            Token::RBracket => write!(f, "`]`"),
+            Token::Comma => write!(f, "`,`"),
+            Token::And => write!(f, "`&&`"),
+            Token::Or => write!(f, "`||`"),
+            Token::Not => write!(f, "`!`"),
+            Token::Implies => write!(f, "`->`"),
+            Token::Cmp(op) => write!(f, "`{}`", op),
+            Token::End => write!(f, "end of rule"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, RuleParseError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let column = i + 1;
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let (token, len) = match (c, next) {
+            (c, _) if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            ('(', _) => (Token::LParen, 1),
+            (')', _) => (Token::RParen, 1),
+            ('[', _) => (Token::LBracket, 1),
+            (']', _) => (Token::RBracket, 1),
+            (',', _) => (Token::Comma, 1),
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('-', Some('>')) => (Token::Implies, 2),
+            ('=', Some('=')) => (Token::Cmp(CmpOp::Eq), 2),
+            ('!', Some('=')) => (Token::Cmp(CmpOp::Ne), 2),
+            ('<', Some('=')) => (Token::Cmp(CmpOp::Le), 2),
+            ('>', Some('=')) => (Token::Cmp(CmpOp::Ge), 2),
+            ('<', _) => (Token::Cmp(CmpOp::Lt), 1),
+            ('>', _) => (Token::Cmp(CmpOp::Gt), 1),
+            ('!', _) => (Token::Not, 1),
+            ('"', _) => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|c| *c == '"')
+                    .ok_or_else(|| RuleParseError::new(column, "unterminated string"))?;
+                let text: String = chars[i + 1..i + 1 + end].iter().collect();
+                (Token::Text(text), end + 2)
+            }
+            (c, _) if c.is_ascii_digit() => {
+                let len = chars[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+                let digits: String = chars[i..i + len].iter().collect();
+                let number = digits
+                    .parse()
+                    .map_err(|_| RuleParseError::new(column, "number is too large"))?;
+                (Token::Number(number), len)
+            }
+            (c, _) if c.is_alphabetic() || c == '_' => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|c| c.is_alphanumeric() || **c == '_')
+                    .count();
+                (Token::Ident(chars[i..i + len].iter().collect()), len)
+            }
+            (c, _) => return Err(RuleParseError::new(column, format!("unexpected character `{}`", c))),
+        };
+        tokens.push((column, token));
+        i += len;
+    }
+    tokens.push((chars.len() + 1, Token::End));
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Display for CmpOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let op = match self {
+            CmpOp::Eq => "==",
+            CmpOp::Ne => "!=",
+            CmpOp::Lt => "<",
+            CmpOp::Le => "<=",
+            CmpOp::Gt => ">",
+            CmpOp::Ge => ">=",
+        };
+        write!(f, "{}", op)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Value {
+    Number(usize),
+    Text(String),
+}
+
+impl Value {
+    fn is_number(&self) -> bool {
+        matches!(self, Value::Number(_))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Id,
+    Name,
+    Quantity,
+    Quality,
+    Size,
+    MaxRow,
+    ItemCount,
+    NormalCount,
+    OverSizedCount,
+    FragileCount,
+    IdCount,
+    IdUnits,
+    NameCount,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        let field = match name {
+            "id" => Field::Id,
+            "name" => Field::Name,
+            "quantity" => Field::Quantity,
+            "quality" => Field::Quality,
+            "size" => Field::Size,
+            "max_row" => Field::MaxRow,
+            "item_count" => Field::ItemCount,
+            "normal_count" => Field::NormalCount,
+            "oversized_count" => Field::OverSizedCount,
+            "fragile_count" => Field::FragileCount,
+            "id_count" => Field::IdCount,
+            "id_units" => Field::IdUnits,
+            "name_count" => Field::NameCount,
+            _ => return None,
+        };
+        Some(field)
+    }
+
+    fn is_number(&self) -> bool {
+        !matches!(self, Field::Name | Field::Quality)
+    }
+
+    fn eval(&self, item: &Item, inventory: &dyn InventoryView) -> Value {
+        match self {
+            Field::Id => Value::Number(item.id),
+            Field::Name => Value::Text(item.name.clone()),
+            Field::Quantity => Value::Number(item.quantity),
+            Field::Quality => Value::Text(item.quality.to_string()),
+            Field::Size => Value::Number(item.size()),
+            Field::MaxRow => match item.quality {
+                Quality::Fragile { max_row, .. } => Value::Number(max_row),
+                _ => Value::Number(usize::MAX), // no restriction
+            },
+            Field::ItemCount => Value::Number(inventory.item_count()),
+            Field::NormalCount => Value::Number(inventory.count_quality(QualityKind::Normal)),
+            Field::OverSizedCount => Value::Number(inventory.count_quality(QualityKind::OverSized)),
+            Field::FragileCount => Value::Number(inventory.count_quality(QualityKind::Fragile)),
+            Field::IdCount => Value::Number(inventory.count_id(item.id)),
+            Field::IdUnits => Value::Number(inventory.units_id(item.id)),
+            Field::NameCount => Value::Number(inventory.count_name(&item.name)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Field(Field),
+    Literal(Value),
+}
+
+impl Operand {
+    fn is_number(&self) -> bool {
+        match self {
+            Operand::Field(field) => field.is_number(),
+            Operand::Literal(value) => value.is_number(),
+        }
+    }
+
+    fn eval(&self, item: &Item, inventory: &dyn InventoryView) -> Value {
+        match self {
+            Operand::Field(field) => field.eval(item, inventory),
+            Operand::Literal(value) => value.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Bool(bool),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Implies(Box<Expr>, Box<Expr>),
+    Cmp(Operand, CmpOp, Operand),
+    In(Operand, Vec<Value>),
+    MaxUnits(usize),
+}
+
+impl Expr {
+    fn eval(&self, item: &Item, inventory: &dyn InventoryView) -> bool {
+        match self {
+            Expr::Bool(value) => *value,
+            Expr::Not(expr) => !expr.eval(item, inventory),
+            Expr::And(lhs, rhs) => lhs.eval(item, inventory) && rhs.eval(item, inventory),
+            Expr::Or(lhs, rhs) => lhs.eval(item, inventory) || rhs.eval(item, inventory),
+            Expr::Implies(lhs, rhs) => !lhs.eval(item, inventory) || rhs.eval(item, inventory),
+            Expr::Cmp(lhs, op, rhs) => {
+                let (lhs, rhs) = (lhs.eval(item, inventory), rhs.eval(item, inventory));
+                match op {
+                    CmpOp::Eq => lhs == rhs,
+                    CmpOp::Ne => lhs != rhs,
+                    CmpOp::Lt => lhs < rhs,
+                    CmpOp::Le => lhs <= rhs,
+                    CmpOp::Gt => lhs > rhs,
+                    CmpOp::Ge => lhs >= rhs,
+                }
+            }
+            Expr::In(operand, values) => values.contains(&operand.eval(item, inventory)),
+            Expr::MaxUnits(limit) => inventory.units_id(item.id) + item.quantity <= *limit,
+        }
+    }
+}
+
+// deepest nesting of parentheses and `!` (recursive descent: deeper rules would overflow the stack)
+const MAX_DEPTH: usize = 64;
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].1
+    }
+
+    fn column(&self) -> usize {
+        self.tokens[self.pos].0
+    }
+
+    fn next(&mut self) -> (usize, Token) {
+        let token = self.tokens[self.pos].clone();
+        if self.pos < self.tokens.len() - 1 {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), RuleParseError> {
+        let (column, token) = self.next();
+        if token != expected {
+            return Err(RuleParseError::new(
+                column,
+                format!("expected {}, found {}", expected, token),
+            ));
+        }
+        Ok(())
+    }
+
+    // parses one level deeper, `column` is the token opening that level
+    fn nested(
+        &mut self,
+        column: usize,
+        parse: fn(&mut Self) -> Result<Expr, RuleParseError>,
+    ) -> Result<Expr, RuleParseError> {
+        if self.depth == MAX_DEPTH {
+            return Err(RuleParseError::new(
+                column,
+                format!("rule nested more than {} levels deep", MAX_DEPTH),
+            ));
+        }
+        self.depth += 1;
+        let expr = parse(self);
+        self.depth -= 1;
+        expr
+    }
+
+    // rule := or ("->" or)?
+    fn rule(&mut self) -> Result<Expr, RuleParseError> {
+        let lhs = self.or()?;
+        if *self.peek() == Token::Implies {
+            self.next();
+            let rhs = self.or()?;
+            return Ok(Expr::Implies(Box::new(lhs), Box::new(rhs)));
+        }
+        Ok(lhs)
+    }
+
+    // or := and ("||" and)*
+    fn or(&mut self) -> Result<Expr, RuleParseError> {
+        let mut lhs = self.and()?;
+        while *self.peek() == Token::Or {
+            self.next();
+            lhs = Expr::Or(Box::new(lhs), Box::new(self.and()?));
+        }
+        Ok(lhs)
+    }
+
+    // and := unary ("&&" unary)*
+    fn and(&mut self) -> Result<Expr, RuleParseError> {
+        let mut lhs = self.unary()?;
+        while *self.peek() == Token::And {
+            self.next();
+            lhs = Expr::And(Box::new(lhs), Box::new(self.unary()?));
+        }
+        Ok(lhs)
+    }
+
+    // unary := "!" unary | primary
+    fn unary(&mut self) -> Result<Expr, RuleParseError> {
+        if *self.peek() == Token::Not {
+            let (column, _) = self.next();
+            return Ok(Expr::Not(Box::new(self.nested(column, Self::unary)?)));
+        }
+        self.primary()
+    }
+
+    // primary := "(" rule ")" | "true" | "false" | "max_units" NUMBER | comparison
+    fn primary(&mut self) -> Result<Expr, RuleParseError> {
+        match self.peek().clone() {
+            Token::LParen => {
+                let (column, _) = self.next();
+                let expr = self.nested(column, Self::rule)?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Token::Ident(name) if name == "true" || name == "false" => {
+                self.next();
+                Ok(Expr::Bool(name == "true"))
+            }
+            Token::Ident(name) if name == "max_units" => {
+                self.next();
+                match self.next() {
+                    (_, Token::Number(limit)) => Ok(Expr::MaxUnits(limit)),
+                    (column, token) => Err(RuleParseError::new(
+                        column,
+                        format!("expected a number after `max_units`, found {}", token),
+                    )),
+                }
+            }
+            _ => self.comparison(),
+        }
+    }
+
+    // comparison := operand (CMP operand | "in" "[" value ("," value)* "]")
+    fn comparison(&mut self) -> Result<Expr, RuleParseError> {
+        let lhs = self.operand()?;
+        let (column, token) = self.next();
+        match token {
+            Token::Cmp(op) => {
+                let rhs_column = self.column();
+                let rhs = self.operand()?;
+                if lhs.is_number() != rhs.is_number() {
+                    return Err(RuleParseError::new(
+                        rhs_column,
+                        format!("can not compare a {} with a {}", kind(&lhs), kind(&rhs)),
+                    ));
+                }
+                Ok(Expr::Cmp(lhs, op, rhs))
+            }
+            Token::Ident(name) if name == "in" => {
+                self.expect(Token::LBracket)?;
+                let mut values = Vec::new();
+                loop {
+                    let value_column = self.column();
+                    let value = self.value()?;
+                    if value.is_number() != lhs.is_number() {
+                        return Err(RuleParseError::new(
+                            value_column,
+                            format!("expected a {} in the list", kind(&lhs)),
+                        ));
+                    }
+                    values.push(value);
+                    match self.next() {
+                        (_, Token::Comma) => continue,
+                        (_, Token::RBracket) => break,
+                        (column, token) => {
+                            return Err(RuleParseError::new(
+                                column,
+                                format!("expected `,` or `]`, found {}", token),
+                            ));
+                        }
+                    }
+                }
+                Ok(Expr::In(lhs, values))
+            }
+            token => Err(RuleParseError::new(
+                column,
+                format!("expected a comparison or `in`, found {}", token),
+            )),
+        }
+    }
+
+    fn operand(&mut self) -> Result<Operand, RuleParseError> {
+        if let Token::Ident(name) = self.peek()
+            && let Some(field) = Field::parse(name)
+        {
+            self.next();
+            return Ok(Operand::Field(field));
+        }
+        Ok(Operand::Literal(self.value()?))
+    }
+
+    // value := NUMBER | STRING | Normal | OverSized | Fragile
+    fn value(&mut self) -> Result<Value, RuleParseError> {
+        match self.next() {
+            (_, Token::Number(n)) => Ok(Value::Number(n)),
+            (_, Token::Text(text)) => Ok(Value::Text(text)),
+            (_, Token::Ident(name)) if ["Normal", "OverSized", "Fragile"].contains(&name.as_str()) => {
+                Ok(Value::Text(name))
+            }
+            (column, Token::Ident(name)) => Err(RuleParseError::new(
+                column,
+                format!("unknown field or quality `{}`", name),
+            )),
+            (column, token) => Err(RuleParseError::new(
+                column,
+                format!("expected a field or a value, found {}", token),
+            )),
+        }
+    }
+}
+
+fn kind(operand: &Operand) -> &'static str {
+    if operand.is_number() { "number" } else { "text" }
+}
+
+/// Filter built from a text rule, the item is allowed if the rule evaluates to true.
+///
+/// Persisted as its source text, which is parsed again when loaded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Rule {
+    source: String,
+    expr: Expr,
+}
+
+impl Rule {
+    pub fn parse(source: &str) -> Result<Self, RuleParseError> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            pos: 0,
+            depth: 0,
+        };
+        let expr = parser.rule()?;
+        if *parser.peek() != Token::End {
+            let (column, token) = parser.next();
+            return Err(RuleParseError::new(
+                column,
+                format!("unexpected {} after the end of the rule", token),
+            ));
+        }
+        Ok(Self {
+            source: source.trim().to_string(),
+            expr,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl TryFrom<String> for Rule {
+    type Error = RuleParseError;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        Rule::parse(&source)
+    }
+}
+
+impl From<Rule> for String {
+    fn from(rule: Rule) -> Self {
+        rule.source
+    }
+}
+
+impl Filter for Rule {
+    fn filter(&self, item: &Item, inventory: &dyn InventoryView) -> bool {
+        self.expr.eval(item, inventory)
+    }
+
+    fn reason(&self, item: &Item, inventory: &dyn InventoryView) -> String {
+        let limit = match &self.expr {
+            Expr::MaxUnits(limit) => Some(limit),
+            Expr::Implies(_, rhs) => match rhs.as_ref() {
+                Expr::MaxUnits(limit) => Some(limit),
+                _ => None,
+            },
+            _ => None,
+        };
+        match limit {
+            Some(limit) => format!(
+                "item {} would reach {} units, limit {}",
+                item.id,
+                inventory.units_id(item.id) + item.quantity,
+                limit
+            ),
+            _ => format!("item {} does not satisfy `{}`", item.id, self.source),
+        }
+    }
+
+    fn to_config(&self) -> Option<FilterConfig> {
+        Some(FilterConfig::Rule(self.clone()))
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Rule({})", self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rule, RuleParseError};
+    use crate::allocators::DefaultAllocator;
+    use crate::errors::ManagerError;
+    use crate::filters::{Filter, FilterConfig};
+    use crate::{Item, Manager, Quality, WarehouseLayout};
+
+    #[test]
+    fn test_rules() {
+        let oversized_limit = Rule::parse("quality == OverSized -> oversized_count < 1").unwrap();
+        let quota = Rule::parse("id in [0, 4, 7] -> max_units 50").unwrap();
+        let names = Rule::parse("!(name == \"Glass\" || quantity > 100) && size <= 2").unwrap();
+        assert_eq!(quota.to_string(), "Rule(id in [0, 4, 7] -> max_units 50)");
+
+        let layout = WarehouseLayout::new(3, 3, 3);
+        let filters: Vec<Box<dyn Filter>> =
+            vec![Box::from(oversized_limit), Box::from(quota), Box::from(names)];
+        let mut manager = Manager::new(layout, DefaultAllocator::default(), filters);
+
+        manager.insert_item(Item::new(1, "Wood", 1, Quality::OverSized { size: 2 })).unwrap();
+        let result = manager.insert_item(Item::new(2, "Wood", 1, Quality::OverSized { size: 1 }));
+        assert!(result.is_err_and(|err| matches!(err, ManagerError::FilteredItem { .. })));
+
+        manager.insert_item(Item::new(4, "Flour", 40, Quality::Normal)).unwrap();
+        let result = manager.insert_item(Item::new(4, "Flour", 20, Quality::Normal));
+        assert!(result.is_err_and(|err| match err {
+            ManagerError::FilteredItem { filters, .. } => {
+                filters.len() == 1 && filters[0].reason == "item 4 would reach 60 units, limit 50"
+            }
+            _ => false,
+        }));
+        assert!(manager.insert_item(Item::new(5, "Flour", 20, Quality::Normal)).is_ok()); // not in the list
+
+        let result = manager.insert_item(Item::new(6, "Glass", 1, Quality::Normal));
+        assert!(result.is_err_and(|err| matches!(err, ManagerError::FilteredItem { .. })));
+        let result = manager.insert_item(Item::new(6, "Salt", 101, Quality::Normal));
+        assert!(result.is_err_and(|err| matches!(err, ManagerError::FilteredItem { .. })));
+    }
+
+    #[test]
+    fn test_rule_parse_errors() {
+        let error = |source| Rule::parse(source).unwrap_err();
+        assert_eq!(
+            error("quality == OverSized && oversize_count < 2"),
+            RuleParseError {
+                column: 25,
+                message: "unknown field or quality `oversize_count`".to_string()
+            }
+        );
+        assert_eq!(error("id in [0, 4 7]").column, 13);
+        assert_eq!(error("name == 3").column, 9); // text compared with a number
+        assert_eq!(error("id == 1 &&").column, 11);
+        assert_eq!(error("(id == 1").column, 9);
+        assert_eq!(error("id == 1 id").column, 9);
+        assert_eq!(error("name == \"Glass").column, 9);
+        assert_eq!(error("id = 1").column, 4);
+        assert_eq!(
+            error("id == 1 &&").pretty("id == 1 &&"),
+            "id == 1 &&\n          ^ expected a field or a value, found end of rule"
+        );
+
+        // deep nesting is an error, not a stack overflow
+        let nested = |depth| format!("{}true{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(Rule::parse(&nested(64)).is_ok());
+        assert_eq!(Rule::parse(&nested(65)).unwrap_err().column, 65);
+        assert!(Rule::parse(&nested(5000)).unwrap_err().message.contains("64 levels"));
+        assert!(Rule::parse(&format!("{}true", "!".repeat(5000))).unwrap_err().message.contains("64 levels"));
+        assert!(serde_json::from_str::<Rule>(&format!("{:?}", nested(5000))).is_err());
+    }
+
+    #[test]
+    fn test_rule_config() {
+        let rule = Rule::parse("fragile_count < 3 || max_row >= 1").unwrap();
+        let json = serde_json::to_string(&rule.to_config().unwrap()).unwrap();
+        assert_eq!(json, r#"{"Rule":"fragile_count < 3 || max_row >= 1"}"#);
+        let config: FilterConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(config.into_filter().to_string(), rule.to_string());
+
+        // rules are validated again when loaded
+        assert!(serde_json::from_str::<FilterConfig>(r#"{"Rule":"id =="}"#).is_err());
+    }
+}
@@ -0,0 +1,170 @@
+use crate::filters::{Filter, FilterConfig};
+use crate::{AllocStrategy, Item, Manager, ManagerError, Slot, WarehouseLayout};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// Bump whenever the layout of [`Snapshot`] changes in a non backwards-compatible way.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Serializable state of a [`Manager`].
+///
+/// Reverse-maps are not stored, they are rebuilt from `inventory` when loading.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot<A> {
+    pub version: u32,
+    pub layout: WarehouseLayout,
+    pub allocator: A,
+    pub filters: Vec<FilterConfig>,
+    #[serde(default)]
+    pub disabled_filters: BTreeSet<String>, // names of the filters that are not evaluated
+    pub inventory: Vec<(Slot, Item)>, // JSON keys must be strings, so store a list of entries
+    #[serde(default)]
+    pub journal_seq: u64, // journal events up to this one are already included
+}
+
+impl<A> Manager<A>
+where
+    A: AllocStrategy + Serialize + DeserializeOwned + Clone,
+{
+    /// Captures the current state. Filters without a [`FilterConfig`] are skipped.
+    pub fn to_snapshot(&self) -> Snapshot<A> {
+        let mut inventory: Vec<(Slot, Item)> = self
+            .inventory
+            .iter()
+            .map(|(slot, item)| (*slot, item.clone()))
+            .collect();
+        inventory.sort_by_key(|(slot, _)| slot.as_tuple()); // stable output
+        Snapshot {
+            version: SNAPSHOT_VERSION,
+            layout: self.layout,
+            allocator: self.allocator.clone(),
+            filters: self.filters.iter().filter_map(|f| f.to_config()).collect(),
+            disabled_filters: self.disabled_filters.clone(),
+            inventory,
+            journal_seq: self.journal_seq,
+        }
+    }
+
+    /// Rebuilds a Manager (including every reverse-map) from a snapshot.
+    pub fn from_snapshot(snapshot: Snapshot<A>) -> Result<Self, ManagerError> {
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(ManagerError::UnsupportedSnapshot {
+                found: snapshot.version,
+                expected: SNAPSHOT_VERSION,
+            });
+        }
+        let filters: Vec<Box<dyn Filter>> = snapshot
+            .filters
+            .into_iter()
+            .map(|config| config.into_filter())
+            .collect();
+        let mut manager = Manager::new(snapshot.layout, snapshot.allocator, filters);
+        for (slot, item) in snapshot.inventory {
+            manager._restore_item(slot, item).map_err(|err| ManagerError::CorruptedSnapshot {
+                reason: err.to_string(),
+            })?;
+        }
+        manager.disabled_filters = snapshot.disabled_filters;
+        manager.journal_seq = snapshot.journal_seq;
+        Ok(manager)
+    }
+
+    pub fn to_json(&self) -> Result<String, ManagerError> {
+        Ok(serde_json::to_string_pretty(&self.to_snapshot())?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ManagerError> {
+        Self::from_snapshot(serde_json::from_str(json)?)
+    }
+
+    /// Writes a JSON snapshot to `path`, overwriting it.
+    pub fn save_snapshot(&self, path: impl AsRef<Path>) -> Result<(), ManagerError> {
+        // write to a temporary file first, so a crash never leaves a half-written snapshot
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, self.to_json()?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Reads a JSON snapshot written by [`Manager::save_snapshot`].
+    pub fn load_snapshot(path: impl AsRef<Path>) -> Result<Self, ManagerError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::allocators::DefaultAllocator;
+    use crate::errors::ManagerError;
+    use crate::filters::{Filter, LimitItemQuantity, LimitOverSized};
+    #[cfg(feature = "alloc-round-robin")]
+    use crate::Slot;
+    use crate::{Item, Manager, Quality, WarehouseLayout};
+    use chrono::{Local, NaiveDateTime, TimeZone};
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let exp_date =
+            NaiveDateTime::parse_from_str("2020-01-01 14:30:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let exp_date = Local.from_local_datetime(&exp_date).unwrap(); // DateTime<Local>
+
+        let filters: Vec<Box<dyn Filter>> = vec![
+            Box::from(LimitOverSized::new(1)),
+            Box::from(LimitItemQuantity::new(0, 50)),
+        ];
+        let layout = WarehouseLayout::new(2, 3, 4);
+        let mut manager = Manager::new(layout, DefaultAllocator::default(), filters);
+        let item0 = Item::new(0, "Flour", 10, Quality::Normal);
+        let item1 = Item::new(1, "Wood", 5, Quality::OverSized { size: 2 });
+        let item2 = Item::new(2, "Glass", 2, Quality::Fragile { expiration_date: exp_date, max_row: 1 });
+        manager.insert_item(item0.clone()).unwrap();
+        manager.insert_item(item1.clone()).unwrap();
+        manager.insert_item(item2.clone()).unwrap();
+
+        let json = manager.to_json().unwrap();
+        let mut loaded = Manager::<DefaultAllocator>::from_json(&json).unwrap();
+
+        assert_eq!(loaded.layout(), &layout);
+        #[cfg(feature = "alloc-round-robin")]
+        assert_eq!(loaded.allocator.get_prev_alloc(), manager.allocator.get_prev_alloc());
+        assert_eq!(loaded.filters.len(), 2);
+        assert_eq!(loaded.map_ids, manager.map_ids);
+        assert_eq!(loaded.map_names, manager.map_names);
+        assert_eq!(loaded.map_slots, manager.map_slots);
+        assert_eq!(loaded.map_occupancy, manager.map_occupancy);
+        assert_eq!(loaded.map_dates, manager.map_dates);
+        assert_eq!(
+            loaded.get_item(0, 0, 2).unwrap().unwrap().timestamp(),
+            manager.get_item(0, 0, 2).unwrap().unwrap().timestamp()
+        );
+        assert_eq!(loaded.find_expired(Local::now()), vec![item2]);
+
+        // filters are restored as well
+        let result = loaded.insert_item(Item::new(3, "Steel", 1, Quality::OverSized { size: 2 }));
+        assert!(result.is_err_and(|err| matches!(err, ManagerError::FilteredItem { .. })));
+        // and so is the allocator state (e.g. the round-robin position)
+        loaded.insert_item(item0.clone()).unwrap();
+        manager.insert_item(item0).unwrap();
+        assert_eq!(loaded.find_id(0), manager.find_id(0));
+        #[cfg(feature = "alloc-round-robin")]
+        assert_eq!(loaded.find_id(0).unwrap()[1], Slot::from((0, 1, 0)));
+    }
+
+    #[test]
+    fn test_snapshot_rejects_bad_input() {
+        let manager = Manager::new(WarehouseLayout::default(), DefaultAllocator::default(), Vec::new());
+        let json = manager.to_json().unwrap().replace("\"version\": 1", "\"version\": 99");
+        let result = Manager::<DefaultAllocator>::from_json(&json);
+        assert!(result.is_err_and(|err| matches!(
+            err,
+            ManagerError::UnsupportedSnapshot { found: 99, .. }
+        )));
+
+        let result = Manager::<DefaultAllocator>::from_json("{ \"version\": ");
+        assert!(result.is_err_and(|err| matches!(err, ManagerError::Serialization { .. })));
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{AllocStrategy, Item, Manager, QualityKind, Slot, WarehouseLayout};
+use chrono::{DateTime, Local};
+
+/// Read-only view of the inventory given to filters.
+///
+/// Aggregates are answered from the [`Manager`] indexes, so admission checks do not need to
+/// scan every pallet.
+pub trait InventoryView {
+    fn layout(&self) -> &WarehouseLayout;
+
+    /// Number of stored items (pallets).
+    fn item_count(&self) -> usize;
+
+    /// Number of stored items with the given id.
+    fn count_id(&self, id: usize) -> usize;
+
+    /// Total units (sum of quantities) of the items with the given id.
+    fn units_id(&self, id: usize) -> usize;
+
+    /// Number of stored items with the given name.
+    fn count_name(&self, name: &str) -> usize;
+
+    /// Total units (sum of quantities) of the items with the given name.
+    fn units_name(&self, name: &str) -> usize;
+
+    /// Number of stored items with both the given name and id.
+    fn count_name_id(&self, name: &str, id: usize) -> usize;
+
+    /// Number of stored items with the given quality, whatever its parameters.
+    fn count_quality(&self, kind: QualityKind) -> usize;
+
+    /// Number of zones in use, OverSized items count once per covered zone.
+    fn occupied_zones(&self) -> usize;
+
+    /// Whether `slot` is in use, either by an item stored there or covered by an OverSized one.
+    fn is_occupied(&self, slot: &Slot) -> bool;
+
+    /// Current time, as given by the manager [`Clock`](crate::Clock).
+    fn now(&self) -> DateTime<Local>;
+
+    /// Every stored item with its slot, for checks no index can answer (linear scan).
+    fn items(&self) -> Box<dyn Iterator<Item = (&Slot, &Item)> + '_>;
+}
+
+impl<A> InventoryView for Manager<A>
+where
+    A: AllocStrategy,
+{
+    fn layout(&self) -> &WarehouseLayout {
+        &self.layout
+    }
+
+    fn item_count(&self) -> usize {
+        self.inventory.len()
+    }
+
+    fn count_id(&self, id: usize) -> usize {
+        self.map_ids.get(&id).map_or(0, |v| *v)
+    }
+
+    fn units_id(&self, id: usize) -> usize {
+        self.map_units.get(&id).map_or(0, |v| *v)
+    }
+
+    fn count_name(&self, name: &str) -> usize {
+        self.map_names.get(name).map_or(0, |v| *v)
+    }
+
+    fn units_name(&self, name: &str) -> usize {
+        self.map_name_units.get(name).map_or(0, |v| *v)
+    }
+
+    fn count_name_id(&self, name: &str, id: usize) -> usize {
+        self.map_name_ids
+            .get(name)
+            .and_then(|ids| ids.get(&id))
+            .map_or(0, |v| *v)
+    }
+
+    fn count_quality(&self, kind: QualityKind) -> usize {
+        self.map_qualities.get(&kind).map_or(0, |v| *v)
+    }
+
+    fn occupied_zones(&self) -> usize {
+        self.map_occupancy.len()
+    }
+
+    fn is_occupied(&self, slot: &Slot) -> bool {
+        self.map_occupancy.contains_key(slot)
+    }
+
+    fn now(&self) -> DateTime<Local> {
+        self.clock.now()
+    }
+
+    fn items(&self) -> Box<dyn Iterator<Item = (&Slot, &Item)> + '_> {
+        Box::new(self.inventory.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InventoryView;
+    use crate::allocators::DefaultAllocator;
+    use crate::{Item, Manager, Quality, QualityKind, Slot, WarehouseLayout};
+
+    #[test]
+    fn test_inventory_view() {
+        let mut manager = Manager::new(WarehouseLayout::new(3, 3, 3), DefaultAllocator::default(), Vec::new());
+        manager.insert_item(Item::new(0, "Flour", 10, Quality::Normal)).unwrap();
+        manager.insert_item(Item::new(1, "Wood", 5, Quality::OverSized { size: 2 })).unwrap();
+        manager.insert_item(Item::new(0, "Flour", 15, Quality::Normal)).unwrap();
+
+        let view: &dyn InventoryView = &manager;
+        assert_eq!(view.item_count(), 3);
+        assert_eq!(view.count_id(0), 2);
+        assert_eq!(view.units_id(0), 25);
+        assert_eq!(view.count_name("Wood"), 1);
+        assert_eq!(view.units_name("Flour"), 25);
+        assert_eq!(view.count_name_id("Flour", 0), 2);
+        assert_eq!(view.count_name_id("Flour", 1), 0);
+        assert_eq!(view.count_quality(QualityKind::Normal), 2);
+        assert_eq!(view.count_quality(QualityKind::OverSized), 1);
+        assert_eq!(view.count_quality(QualityKind::Fragile), 0);
+        assert_eq!(view.occupied_zones(), 4);
+        assert!(view.is_occupied(&Slot::from((0, 0, 2)))); // covered by the OverSized item
+        assert_eq!(view.items().count(), 3);
+
+        // counters follow removals
+        manager.remove_item(0, 0, 0).unwrap();
+        manager.remove_item(0, 0, 2).unwrap();
+        assert_eq!(manager.units_id(0), 15);
+        assert_eq!(manager.count_quality(QualityKind::OverSized), 0);
+        assert_eq!(manager.occupied_zones(), 1);
+        assert!(!manager.map_qualities.contains_key(&QualityKind::OverSized));
+
+        let slot = manager.find_id(0).unwrap()[0];
+        manager.remove_item(slot.row(), slot.shelf(), slot.zone()).unwrap();
+        assert_eq!(manager.units_id(0), 0);
+        assert!(manager.map_units.is_empty());
+        assert!(manager.map_name_units.is_empty());
+        assert!(manager.map_name_ids.is_empty());
+    }
+}